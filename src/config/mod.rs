@@ -0,0 +1,3 @@
+pub use self::user_config::{HostCredentials, UserConfig};
+
+mod user_config;
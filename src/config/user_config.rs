@@ -0,0 +1,359 @@
+use crate::constants;
+use crate::prelude::*;
+use crate::types::{ColorChoice, ConflictStrategy};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// The user level settings read from `$XDG_CONFIG_HOME/boilerplato/config.yml` on top of the system wide ones in
+/// `/etc/boilerplato/config.yml`, and overridden by the nearest `.boilerplatorc` file of the current project. All
+/// the fields are optional.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UserConfig {
+    /// The template forge prefixes mapped to their base URLs e.g. `corp: https://git.corp.example/`, the
+    /// built-in prefixes can be overridden too.
+    #[serde(default)]
+    pub forges: HashMap<String, String>,
+    /// The git credentials per host e.g. `git.corp.example`, used for the private template repos.
+    #[serde(default)]
+    pub credentials: HashMap<String, HostCredentials>,
+    /// The URL prefixes mapped to their replacements like git's `insteadOf` e.g. to use a mirror, the longest
+    /// matching prefix wins.
+    #[serde(default)]
+    pub url_rewrites: HashMap<String, String>,
+    /// The short names of the template specifiers e.g. `api: corp:platform/api-template#v3`.
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// The default answers of the template data e.g. `author`, they replace the template's default values.
+    #[serde(default)]
+    pub answers: HashMap<String, Value>,
+    /// The GitHub organisations the bare template names and the search refer to, defaults to `boilerplato`.
+    #[serde(default)]
+    pub orgs: Vec<String>,
+    /// The GitHub API base URL for the search e.g. `https://github.corp.example/api/v3` for GitHub Enterprise.
+    pub github_api_url: Option<String>,
+    /// How long the search results and the remote registry indexes are cached for, in seconds.
+    pub search_cache_ttl: Option<u64>,
+    /// How long the remote archive templates are used from the cache before they're downloaded again, in seconds.
+    pub archive_cache_ttl: Option<u64>,
+    /// The template registry index URLs or paths, the search queries all of them and the bare template names are
+    /// looked up in them before the orgs.
+    #[serde(default)]
+    pub registries: Vec<String>,
+    /// The default of the `--on-conflict` flag.
+    pub on_conflict: Option<ConflictStrategy>,
+    /// Whether to initialize a git repo in the generated project, defaults to true.
+    pub git_init: Option<bool>,
+    /// The default of the `--color` flag.
+    pub color: Option<ColorChoice>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HostCredentials {
+    pub username: Option<String>,
+    pub token: Option<String>,
+    /// The env var holding the token, it keeps the token out of the config file.
+    pub token_env: Option<String>,
+}
+
+impl UserConfig {
+    pub fn parse(text: &str) -> crate::Result<UserConfig> {
+        if text.trim().is_empty() {
+            return Ok(UserConfig::default());
+        }
+
+        serde_yaml::from_str::<UserConfig>(text).wrap()
+    }
+
+    /// Loads the system, the user and the project config files, the later ones take precedence. A missing file
+    /// is the same as an empty config.
+    pub fn load() -> crate::Result<UserConfig> {
+        let project_config = match UserConfig::project_file_path() {
+            Some(path) => {
+                let mut config = UserConfig::load_from(path.as_path())?;
+                // The project files are often checked out from elsewhere, so they can't send the tokens anywhere.
+                config.credentials.clear();
+                config
+            }
+            None => UserConfig::default(),
+        };
+
+        Ok(UserConfig::load_trusted()?.merge(project_config))
+    }
+
+    /// Loads only the system and the user config files, which decide where the tokens can be sent to.
+    pub fn load_trusted() -> crate::Result<UserConfig> {
+        let system_config_path = env::var_os(constants::SYSTEM_CONFIG_FILE_ENV_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(constants::SYSTEM_CONFIG_FILE_PATH));
+
+        let system_config = if system_config_path.exists() {
+            UserConfig::load_from(system_config_path.as_path())?
+        } else {
+            UserConfig::default()
+        };
+
+        let user_config = match UserConfig::file_path() {
+            Some(path) if path.exists() => UserConfig::load_from(path.as_path())?,
+            _ => UserConfig::default(),
+        };
+
+        Ok(system_config.merge(user_config))
+    }
+
+    pub fn load_from(path: &Path) -> crate::Result<UserConfig> {
+        let text = fs::read_to_string(path).context(format!(
+            "Couldn't read the user config file: {}",
+            path.to_str().unwrap_or("")
+        ))?;
+
+        UserConfig::parse(text.as_str()).context(format!("Invalid user config file: {}", path.to_str().unwrap_or("")))
+    }
+
+    /// The config file path, `$BOILERPLATO_CONFIG` takes precedence over the XDG config dir.
+    pub fn file_path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os(constants::USER_CONFIG_FILE_ENV_VAR) {
+            return Some(PathBuf::from(path));
+        }
+
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|dir| dir.join(constants::APP_NAME).join(constants::USER_CONFIG_FILE_NAME))
+    }
+
+    /// Finds the nearest `.boilerplatorc` file in the current dir or in its ancestors.
+    pub fn project_file_path() -> Option<PathBuf> {
+        let current_dir = env::current_dir().ok()?;
+
+        current_dir
+            .ancestors()
+            .map(|dir| dir.join(constants::PROJECT_CONFIG_FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// Finds the base URL of a forge prefix, the user defined ones take precedence over the built-in ones.
+    pub fn forge_base_url(&self, prefix: &str) -> Option<String> {
+        self.forges.get(prefix).map(|url| url.to_owned()).or_else(|| {
+            constants::TEMPLATE_BUILT_IN_FORGES
+                .iter()
+                .find(|(name, _)| *name == prefix)
+                .map(|(_, url)| (*url).to_owned())
+        })
+    }
+
+    pub fn host_credentials(&self, host: &str) -> Option<&HostCredentials> {
+        self.credentials.get(host)
+    }
+
+    /// Whether the host is named in the config by the credentials, the forges, the URL rewrite targets or the
+    /// GitHub API base, the hosts of the built-in forges are always known.
+    pub fn is_known_host(&self, host: &str) -> bool {
+        let url_host = |url: &str| {
+            Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(|h| h.to_owned()))
+        };
+
+        self.credentials.contains_key(host)
+            || constants::TEMPLATE_BUILT_IN_FORGES
+                .iter()
+                .map(|(_, url)| *url)
+                .chain(self.forges.values().map(|url| url.as_str()))
+                .chain(self.url_rewrites.values().map(|url| url.as_str()))
+                .chain(self.github_api_url.iter().map(|url| url.as_str()))
+                .any(|url| url_host(url).as_deref() == Some(host))
+    }
+
+    /// Rewrites the URL with the longest matching rewrite rule, the URL is returned as is if no rule matches.
+    pub fn rewrite_url(&self, url: &str) -> String {
+        self.url_rewrites
+            .iter()
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, replacement)| format!("{}{}", replacement, &url[prefix.len()..]))
+            .unwrap_or_else(|| url.to_owned())
+    }
+
+    /// The GitHub API base URL, `$BOILERPLATO_GITHUB_API_URL` takes precedence over the config.
+    pub fn github_api_url(&self) -> String {
+        env::var(constants::GITHUB_API_URL_ENV_VAR)
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .or_else(|| self.github_api_url.clone())
+            .unwrap_or_else(|| constants::GITHUB_API_URL.to_owned())
+    }
+
+    pub fn search_cache_ttl(&self) -> u64 {
+        self.search_cache_ttl.unwrap_or(constants::SEARCH_CACHE_DEFAULT_TTL)
+    }
+
+    pub fn archive_cache_ttl(&self) -> u64 {
+        self.archive_cache_ttl.unwrap_or(constants::ARCHIVE_CACHE_DEFAULT_TTL)
+    }
+
+    /// The template specifier the alias stands for, if the template is an alias.
+    pub fn alias(&self, template: &str) -> Option<&str> {
+        self.aliases.get(template.trim()).map(|spec| spec.as_str())
+    }
+
+    pub fn orgs(&self) -> Vec<&str> {
+        if self.orgs.is_empty() {
+            vec![constants::BOILERPLATO_GITHUB_HANDLE]
+        } else {
+            self.orgs.iter().map(|org| org.as_str()).collect()
+        }
+    }
+
+    /// Overlays the other config on this one, the other config's settings take precedence. The org and the
+    /// registry lists are replaced as a whole as their order matters.
+    fn merge(mut self, other: UserConfig) -> UserConfig {
+        self.forges.extend(other.forges);
+        self.credentials.extend(other.credentials);
+        self.url_rewrites.extend(other.url_rewrites);
+        self.aliases.extend(other.aliases);
+        self.answers.extend(other.answers);
+        if !other.orgs.is_empty() {
+            self.orgs = other.orgs;
+        }
+        if !other.registries.is_empty() {
+            self.registries = other.registries;
+        }
+        self.on_conflict = other.on_conflict.or(self.on_conflict);
+        self.git_init = other.git_init.or(self.git_init);
+        self.color = other.color.or(self.color);
+        self.github_api_url = other.github_api_url.or(self.github_api_url);
+        self.search_cache_ttl = other.search_cache_ttl.or(self.search_cache_ttl);
+        self.archive_cache_ttl = other.archive_cache_ttl.or(self.archive_cache_ttl);
+        self
+    }
+}
+
+impl HostCredentials {
+    pub fn token(&self) -> Option<String> {
+        self.token
+            .clone()
+            .or_else(|| self.token_env.as_ref().and_then(|name| env::var(name).ok()))
+            .filter(|token| !token.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_config_forges() {
+        let config = UserConfig::parse(
+            "\
+forges:
+  corp: https://git.corp.example/
+  gitlab: https://gitlab.corp.example/
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.forge_base_url("corp"),
+            Some("https://git.corp.example/".to_owned())
+        );
+        assert_eq!(
+            config.forge_base_url("gitlab"),
+            Some("https://gitlab.corp.example/".to_owned())
+        );
+        assert_eq!(
+            config.forge_base_url("bitbucket"),
+            Some("https://bitbucket.org/".to_owned())
+        );
+        assert_eq!(config.forge_base_url("unknown"), None);
+        assert!(UserConfig::parse("").unwrap().forges.is_empty());
+    }
+
+    #[test]
+    fn test_user_config_url_rewrites() {
+        let system_config = UserConfig::parse(
+            "\
+urlRewrites:
+  https://github.com/: https://mirror.internal/github/
+  https://api.github.com/: https://mirror.internal/github-api/
+",
+        )
+        .unwrap();
+        let user_config = UserConfig::parse(
+            "\
+urlRewrites:
+  https://github.com/boilerplato/: https://templates.internal/
+",
+        )
+        .unwrap();
+        let config = system_config.merge(user_config);
+
+        assert_eq!(
+            config.rewrite_url("https://github.com/boilerplato/rust-cli"),
+            "https://templates.internal/rust-cli"
+        );
+        assert_eq!(
+            config.rewrite_url("https://github.com/john/bar"),
+            "https://mirror.internal/github/john/bar"
+        );
+        assert_eq!(
+            config.rewrite_url("https://api.github.com/search/repositories?q=rust"),
+            "https://mirror.internal/github-api/search/repositories?q=rust"
+        );
+        assert_eq!(
+            config.rewrite_url("https://gitlab.com/group/repo"),
+            "https://gitlab.com/group/repo"
+        );
+    }
+
+    #[test]
+    fn test_user_config_project_overrides() {
+        let user_config = UserConfig::parse(
+            "\
+aliases:
+  api: corp:platform/api-template#v3
+  web: john/web-template
+answers:
+  author: John
+  license: MIT
+orgs: [our-org, boilerplato]
+onConflict: skip
+gitInit: false
+color: never
+",
+        )
+        .unwrap();
+        let project_config = UserConfig::parse(
+            "\
+aliases:
+  web: corp:platform/web-template
+answers:
+  license: Apache-2.0
+onConflict: prompt
+",
+        )
+        .unwrap();
+        let config = user_config.merge(project_config);
+
+        assert_eq!(config.alias("api"), Some("corp:platform/api-template#v3"));
+        assert_eq!(config.alias("web"), Some("corp:platform/web-template"));
+        assert_eq!(config.alias("john/web-template"), None);
+        assert_eq!(config.answers.get("author"), Some(&Value::String("John".to_owned())));
+        assert_eq!(
+            config.answers.get("license"),
+            Some(&Value::String("Apache-2.0".to_owned()))
+        );
+        assert_eq!(config.orgs(), vec!["our-org", "boilerplato"]);
+        assert_eq!(config.on_conflict, Some(ConflictStrategy::Prompt));
+        assert_eq!(config.git_init, Some(false));
+        assert_eq!(config.color, Some(ColorChoice::Never));
+        assert_eq!(UserConfig::default().orgs(), vec![constants::BOILERPLATO_GITHUB_HANDLE]);
+    }
+}
@@ -2,53 +2,129 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use std::ffi::OsStr;
 
-pub const APP_NAME: &'static str = env!("CARGO_PKG_NAME");
-pub const APP_VERSION: &'static str = env!("CARGO_PKG_VERSION");
-pub const APP_DESCRIPTION: &'static str = env!("CARGO_PKG_DESCRIPTION");
-pub const APP_AUTHOR: &'static str = "Rousan Ali <hello@rousan.io> (https://rousan.io)";
+pub const APP_NAME: &str = env!("CARGO_PKG_NAME");
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const APP_DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
+pub const APP_AUTHOR: &str = "Rousan Ali <hello@rousan.io> (https://rousan.io)";
 
-pub const TEMPLATE_PREFIX_FILE: &'static str = "file:";
-pub const TEMPLATE_PREFIX_HTTPS: &'static str = "https:";
-pub const TEMPLATE_PREFIX_HTTP: &'static str = "http:";
-pub const TEMPLATE_IN_BUILT_PREFIX: &'static str = "https://github.com/boilerplato/";
-pub const TEMPLATE_GITHUB_PREFIX: &'static str = "https://github.com/";
-pub const TEMPLATE_GIT_REPO_BRANCH: &'static str = "master";
+pub const TEMPLATE_PREFIX_FILE: &str = "file:";
+pub const TEMPLATE_PREFIX_HTTPS: &str = "https:";
+pub const TEMPLATE_PREFIX_HTTP: &str = "http:";
+pub const TEMPLATE_BUILT_IN_FORGES: [(&str, &str); 4] = [
+    ("github", "https://github.com/"),
+    ("gitlab", "https://gitlab.com/"),
+    ("bitbucket", "https://bitbucket.org/"),
+    ("sourcehut", "https://git.sr.ht/"),
+];
+pub const TEMPLATE_ARCHIVE_EXTENSION_ZIP: &str = ".zip";
+pub const TEMPLATE_ARCHIVE_EXTENSIONS: [&str; 3] = [".tar.gz", ".tgz", TEMPLATE_ARCHIVE_EXTENSION_ZIP];
+pub const TEMPLATE_GIT_URL_PREFIXES: [&str; 4] = ["ssh://", "git://", "git+ssh://", "ssh+git://"];
+pub const TEMPLATE_GITHUB_PREFIX: &str = "https://github.com/";
+pub const TEMPLATE_REF_SEPARATOR: char = '#';
+pub const TEMPLATE_VERSION_SEPARATOR: char = '@';
+pub const TEMPLATE_SUBDIR_SEPARATOR: &str = "//";
 
-pub const TEMPLATE_ENGINE_HANDLEBARS: &'static str = "handlebars";
+pub const TEMPLATE_ENGINE_HANDLEBARS: &str = "handlebars";
 
-pub const TEMPLATE_CONFIG_FILE_JSON: &'static str = "boilerplato.json";
-pub const TEMPLATE_CONFIG_FILE_YAML: &'static str = "boilerplato.yaml";
-pub const TEMPLATE_CONFIG_FILE_YML: &'static str = "boilerplato.yml";
-pub const TEMPLATE_BOILERPLATO_IGNORE_FILE_NAME: &'static str = ".boilerplatoignore";
-pub const TEMPLATE_GIT_IGNORE_FILE_NAME: &'static str = ".gitignore";
+pub const TEMPLATE_CONFIG_FILE_JSON: &str = "boilerplato.json";
+pub const TEMPLATE_CONFIG_FILE_YAML: &str = "boilerplato.yaml";
+pub const TEMPLATE_CONFIG_FILE_YML: &str = "boilerplato.yml";
+pub const TEMPLATE_BOILERPLATO_IGNORE_FILE_NAME: &str = ".boilerplatoignore";
+pub const TEMPLATE_GIT_IGNORE_FILE_NAME: &str = ".gitignore";
+pub const TEMPLATE_GIT_MODULES_FILE_NAME: &str = ".gitmodules";
+pub const GIT_ATTRIBUTES_FILE_NAME: &str = ".gitattributes";
+pub const USER_CONFIG_FILE_NAME: &str = "config.yml";
+pub const USER_CONFIG_FILE_ENV_VAR: &str = "BOILERPLATO_CONFIG";
+pub const PROJECT_CONFIG_FILE_NAME: &str = ".boilerplatorc";
+pub const SYSTEM_CONFIG_FILE_PATH: &str = "/etc/boilerplato/config.yml";
+pub const SYSTEM_CONFIG_FILE_ENV_VAR: &str = "BOILERPLATO_SYSTEM_CONFIG";
 
-pub const TEMPLATE_TYPE_BOOL_POSSIBLE_TRUTHY_INPUTS: [&'static str; 2] = ["yes", "y"];
-pub const TEMPLATE_TYPE_BOOL_POSSIBLE_FALSY_INPUTS: [&'static str; 2] = ["no", "n"];
+pub const CACHE_DIR_ENV_VAR: &str = "BOILERPLATO_CACHE_DIR";
+/// How long a cached remote archive template is used before it's downloaded again, in seconds.
+pub const ARCHIVE_CACHE_DEFAULT_TTL: u64 = 86400;
 
-pub const TEMPLATE_TYPE_SEMVER_DEFAULT_VALUE: &'static str = "1.0.0";
-pub const TEMPLATE_DEFAULT_TEMPLATE_PATH: &'static str = ".";
-pub const TEMPLATE_DEFAULT_FILE_EXTENSION: &'static str = ".boiler";
+pub const GIT_TOKEN_ENV_VAR: &str = "BOILERPLATO_GIT_TOKEN";
+pub const GITHUB_TOKEN_ENV_VAR: &str = "GITHUB_TOKEN";
+/// The hosts with their token env vars and the usernames the tokens are sent with.
+pub const GIT_HOST_TOKEN_ENV_VARS: [(&str, &str, &str); 2] = [
+    ("github.com", GITHUB_TOKEN_ENV_VAR, "x-access-token"),
+    ("gitlab.com", "GITLAB_TOKEN", "oauth2"),
+];
+pub const GIT_TOKEN_DEFAULT_USERNAME: &str = "oauth2";
+pub const GIT_SSH_DEFAULT_USERNAME: &str = "git";
+pub const REDACTED_CREDENTIALS: &str = "***";
+pub const PROJECT_ANSWERS_FILE_PATH: &str = ".boilerplato/answers.yml";
 
-pub const TEMPLATE_DATA_APP_NAME: &'static str = "appName";
+pub const TEMPLATE_TYPE_BOOL_POSSIBLE_TRUTHY_INPUTS: [&str; 3] = ["yes", "y", "true"];
+pub const TEMPLATE_TYPE_BOOL_POSSIBLE_FALSY_INPUTS: [&str; 3] = ["no", "n", "false"];
 
-pub const TEMPLATE_OS_FLAG_ALL: &'static str = "all";
+pub const TEMPLATE_TYPE_SEMVER_DEFAULT_VALUE: &str = "1.0.0";
+pub const TEMPLATE_DEFAULT_TEMPLATE_PATH: &str = ".";
+pub const TEMPLATE_DEFAULT_FILE_EXTENSION: &str = ".boiler";
 
-pub const TEMPLATE_EXTRA_VAR_APP_NAME: &'static str = "APP_NAME";
-pub const TEMPLATE_EXTRA_VAR_PROJECT_DIR_FULL_PATH: &'static str = "APP_FULL_PATH";
-pub const TEMPLATE_EXTRA_VAR_PROJECT_DIR_REL_PATH: &'static str = "APP_REL_PATH";
-pub const TEMPLATE_EXTRA_VAR_TEMPLATE_PATH: &'static str = "TEMPLATE_PATH";
-pub const TEMPLATE_EXTRA_VAR_TEMPLATE_SOURCE_PATH: &'static str = "TEMPLATE_SOURCE_PATH";
+pub const TEMPLATE_DATA_APP_NAME: &str = "appName";
+pub const TEMPLATE_DATA_ASSIGNMENT_SEPARATOR: char = '=';
 
-pub const SEARCH_REPO_GITHUB_API_ENDPOINT: &'static str = "https://api.github.com/search/repositories";
-pub const BOILERPLATO_GITHUB_HANDLE: &'static str = "boilerplato";
+pub const TEMPLATE_OS_FLAG_ALL: &str = "all";
+
+pub const CONFLICT_STRATEGY_BACKUP: &str = "backup";
+pub const CONFLICT_STRATEGY_OVERWRITE: &str = "overwrite";
+pub const CONFLICT_STRATEGY_SKIP: &str = "skip";
+pub const CONFLICT_STRATEGY_PROMPT: &str = "prompt";
+pub const CONFLICT_STRATEGY_FAIL: &str = "fail";
+pub const CONFLICT_STRATEGIES: [&str; 5] = [
+    CONFLICT_STRATEGY_BACKUP,
+    CONFLICT_STRATEGY_OVERWRITE,
+    CONFLICT_STRATEGY_SKIP,
+    CONFLICT_STRATEGY_PROMPT,
+    CONFLICT_STRATEGY_FAIL,
+];
+pub const CONFLICT_BACKUP_FILE_EXTENSION: &str = ".old";
+pub const UPDATE_NEW_FILE_EXTENSION: &str = ".new";
+
+pub const COLOR_CHOICE_AUTO: &str = "auto";
+pub const COLOR_CHOICE_ALWAYS: &str = "always";
+pub const COLOR_CHOICE_NEVER: &str = "never";
+pub const COLOR_CHOICES: [&str; 3] = [COLOR_CHOICE_AUTO, COLOR_CHOICE_ALWAYS, COLOR_CHOICE_NEVER];
+
+pub const TEMPLATE_EXTRA_VAR_APP_NAME: &str = "APP_NAME";
+pub const TEMPLATE_EXTRA_VAR_PROJECT_DIR_FULL_PATH: &str = "APP_FULL_PATH";
+pub const TEMPLATE_EXTRA_VAR_PROJECT_DIR_REL_PATH: &str = "APP_REL_PATH";
+pub const TEMPLATE_EXTRA_VAR_TEMPLATE_PATH: &str = "TEMPLATE_PATH";
+pub const TEMPLATE_EXTRA_VAR_TEMPLATE_SOURCE_PATH: &str = "TEMPLATE_SOURCE_PATH";
+
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+pub const GITHUB_API_URL_ENV_VAR: &str = "BOILERPLATO_GITHUB_API_URL";
+pub const GITHUB_HOST: &str = "github.com";
+pub const SEARCH_REPO_GITHUB_API_PATH: &str = "search/repositories";
+pub const BOILERPLATO_GITHUB_HANDLE: &str = "boilerplato";
+pub const SEARCH_DEFAULT_LIMIT: usize = 30;
+/// How long the search results and the registry indexes are cached for, in seconds.
+pub const SEARCH_CACHE_DEFAULT_TTL: u64 = 3600;
+/// The maximum page size of the GitHub search API.
+pub const SEARCH_MAX_LIMIT: usize = 100;
+pub const SEARCH_SORT_STARS: &str = "stars";
+pub const SEARCH_SORT_UPDATED: &str = "updated";
+pub const SEARCH_SORT_NAME: &str = "name";
+pub const SEARCH_SORTS: [&str; 3] = [SEARCH_SORT_STARS, SEARCH_SORT_UPDATED, SEARCH_SORT_NAME];
+
+pub const OUTPUT_FORMAT_TEXT: &str = "text";
+pub const OUTPUT_FORMAT_JSON: &str = "json";
+pub const OUTPUT_FORMATS: [&str; 2] = [OUTPUT_FORMAT_TEXT, OUTPUT_FORMAT_JSON];
 
 lazy_static! {
     pub static ref RE_COMMA_SEPARATOR: Regex = Regex::new(r"[,\s]+").unwrap();
 }
 
+lazy_static! {
+    pub static ref RE_URL_CREDENTIALS: Regex =
+        Regex::new(r"(?P<scheme>[a-zA-Z][a-zA-Z\d+.-]*://)(?P<userinfo>[^/@\s]+)@").unwrap();
+}
+
 lazy_static! {
     pub static ref TEMPLATE_IGNORED_FILES: Vec<&'static OsStr> = vec![
         ".git".as_ref(),
+        TEMPLATE_GIT_MODULES_FILE_NAME.as_ref(),
         "node_modules".as_ref(),
         TEMPLATE_CONFIG_FILE_JSON.as_ref(),
         TEMPLATE_CONFIG_FILE_YAML.as_ref(),
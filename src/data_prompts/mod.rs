@@ -1,14 +1,29 @@
 use crate::constants;
 use crate::prelude::*;
-use crate::types::{TemplateConfig, TemplateData, TemplateDataType};
+use crate::types::{ConfigFileType, TemplateConfig, TemplateData, TemplateDataType};
+use crate::utils;
 use colored::*;
 use semver::Version;
 use serde_json::{Number, Value};
 use std::collections::HashMap;
+use std::fs;
 use std::io::{self, BufRead, Write};
+use std::path::Path;
 
-pub fn ask_data(config: &TemplateConfig) -> crate::Result<HashMap<&str, Value>> {
+pub fn ask_data<'a>(
+    config: &'a TemplateConfig,
+    preset_data: &HashMap<String, Value>,
+    use_defaults: bool,
+) -> crate::Result<HashMap<&'a str, Value>> {
     let mut template_data = HashMap::with_capacity(config.data.len());
+    let interactive = is_interactive();
+    let mut missing_data = Vec::new();
+
+    for name in preset_data.keys() {
+        if !config.data.iter().any(|d| &d.name == name) {
+            println!("{} Unknown template data `{}`, ignoring it", "Warning:".yellow(), name);
+        }
+    }
 
     for d in config.data.iter() {
         if d.name == constants::TEMPLATE_DATA_APP_NAME {
@@ -19,19 +34,43 @@ pub fn ask_data(config: &TemplateConfig) -> crate::Result<HashMap<&str, Value>>
             continue;
         }
 
+        if let Some(val) = preset_data.get(&d.name) {
+            let val = validate_value(d, val).context(format!("Invalid value for template data `{}`", d.name))?;
+            template_data.insert(d.name.as_str(), val);
+            continue;
+        }
+
+        if !d.required && (use_defaults || !interactive) {
+            template_data.insert(
+                d.name.as_str(),
+                d.default_value.clone().unwrap_or_else(|| d.data_type.default_value()),
+            );
+            continue;
+        }
+
+        if !interactive {
+            missing_data.push(d.name.as_str());
+            continue;
+        }
+
         template_data.insert(d.name.as_str(), ask_a_single_data(d)?);
     }
 
+    if !missing_data.is_empty() {
+        return Err(crate::Error::new(format!(
+            "The stdin is not a terminal and the following required template data were not provided: {}\n\
+             Provide them with --data <answers-file> or --set <name>=<value>",
+            missing_data.join(", ")
+        )));
+    }
+
     Ok(template_data)
 }
 
 pub fn ask_a_single_data(data_config: &TemplateData) -> crate::Result<Value> {
     let msg = get_data_massage(data_config);
 
-    let stdin = io::stdin();
     let stdout = io::stdout();
-
-    let mut r_handle = stdin.lock();
     let mut w_handle = stdout.lock();
 
     loop {
@@ -40,8 +79,12 @@ pub fn ask_a_single_data(data_config: &TemplateData) -> crate::Result<Value> {
         w_handle.write_fmt(format_args!("{}", " › ".bright_black())).wrap()?;
         w_handle.flush().wrap()?;
 
-        let mut line = String::with_capacity(10);
-        r_handle.read_line(&mut line).wrap()?;
+        let line = read_input_line(data_config.data_type == TemplateDataType::Secret)?.ok_or_else(|| {
+            crate::Error::new(format!(
+                "Reached the end of input while reading the template data: {}",
+                data_config.name
+            ))
+        })?;
 
         let validation = validate_input(data_config, line.as_str());
         match validation {
@@ -60,6 +103,59 @@ pub fn ask_a_single_data(data_config: &TemplateData) -> crate::Result<Value> {
     }
 }
 
+fn read_input_line(hidden: bool) -> crate::Result<Option<String>> {
+    if hidden {
+        return rpassword::read_password().map(Some).wrap();
+    }
+
+    let mut line = String::with_capacity(10);
+    if io::stdin().lock().read_line(&mut line).wrap()? == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(line))
+}
+
+pub fn load_data_file<P: AsRef<Path>>(path: P) -> crate::Result<HashMap<String, Value>> {
+    let path = path.as_ref();
+
+    let file_type = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => ConfigFileType::JSON,
+        _ => ConfigFileType::YAML,
+    };
+
+    let text = fs::read_to_string(path).context(format!(
+        "Couldn't read the template data file: {}",
+        path.to_str().unwrap_or("")
+    ))?;
+
+    let data = match file_type {
+        ConfigFileType::JSON => serde_json::from_str::<HashMap<String, Value>>(text.as_str()).wrap(),
+        ConfigFileType::YAML => serde_yaml::from_str::<HashMap<String, Value>>(text.as_str()).wrap(),
+    };
+
+    data.context(format!(
+        "Invalid template data file, it must be a map of data name to value: {}",
+        path.to_str().unwrap_or("")
+    ))
+}
+
+pub fn parse_data_assignment(assignment: &str) -> crate::Result<(String, Value)> {
+    let mut parts = assignment.splitn(2, constants::TEMPLATE_DATA_ASSIGNMENT_SEPARATOR);
+
+    match (parts.next().map(|name| name.trim()), parts.next()) {
+        (Some(name), Some(value)) if !name.is_empty() => Ok((name.to_owned(), Value::String(value.to_owned()))),
+        _ => Err(crate::Error::new(format!(
+            "Invalid template data assignment, expected <name>=<value>: {}",
+            assignment
+        ))),
+    }
+}
+
+pub fn is_interactive() -> bool {
+    atty::is(atty::Stream::Stdin)
+}
+
 pub fn get_data_massage(data_config: &TemplateData) -> String {
     let mut msg = data_config.message.trim();
 
@@ -69,7 +165,7 @@ pub fn get_data_massage(data_config: &TemplateData) -> String {
     }
 
     if data_config.required {
-        return format!("{}", msg);
+        return msg.to_string();
     }
 
     if let Some(ref value) = data_config.default_value {
@@ -77,11 +173,12 @@ pub fn get_data_massage(data_config: &TemplateData) -> String {
             TemplateDataType::String => {
                 let value = value.as_str().unwrap_or("").trim();
                 if value.is_empty() {
-                    format!("{}", msg)
+                    msg.to_string()
                 } else {
                     format!("{} ({})", msg, value)
                 }
             }
+            TemplateDataType::Secret => msg.to_string(),
             TemplateDataType::Number => format!("{} ({})", msg, value.as_f64().unwrap_or(0_f64)),
             TemplateDataType::Bool => {
                 let bool_str = if value.as_bool().unwrap_or(false) { "yes" } else { "no" };
@@ -91,14 +188,13 @@ pub fn get_data_massage(data_config: &TemplateData) -> String {
                 let arr_str = value
                     .as_array()
                     .into_iter()
-                    .map(|x| x.iter())
-                    .flatten()
+                    .flat_map(|x| x.iter())
                     .map(|x: &Value| x.as_str().unwrap_or(""))
                     .collect::<Vec<&str>>()
                     .join(", ");
 
                 if arr_str.is_empty() {
-                    format!("{}", msg)
+                    msg.to_string()
                 } else {
                     format!("{} ([{}])", msg, arr_str)
                 }
@@ -107,14 +203,13 @@ pub fn get_data_massage(data_config: &TemplateData) -> String {
                 let arr_str = value
                     .as_array()
                     .into_iter()
-                    .map(|x| x.iter())
-                    .flatten()
+                    .flat_map(|x| x.iter())
                     .map(|x: &Value| x.as_f64().unwrap_or(0_f64).to_string())
                     .collect::<Vec<String>>()
                     .join(", ");
 
                 if arr_str.is_empty() {
-                    format!("{}", msg)
+                    msg.to_string()
                 } else {
                     format!("{} ([{}])", msg, arr_str)
                 }
@@ -122,17 +217,31 @@ pub fn get_data_massage(data_config: &TemplateData) -> String {
             TemplateDataType::Semver => {
                 let value = value.as_str().unwrap_or("").trim();
                 if value.is_empty() {
-                    format!("{}", value)
+                    value.to_string()
                 } else {
                     format!("{} ({})", msg, value)
                 }
             }
         }
     } else {
-        format!("{}", msg)
+        msg.to_string()
     }
 }
 
+pub fn validate_value(data_config: &TemplateData, value: &Value) -> crate::Result<Value> {
+    let input = match value {
+        Value::Bool(b) => utils::or(*b, "yes", "no").to_owned(),
+        Value::Array(ref items) => items
+            .iter()
+            .map(utils::json_val_to_actual_str)
+            .collect::<Vec<String>>()
+            .join(", "),
+        _ => utils::json_val_to_actual_str(value),
+    };
+
+    validate_input(data_config, input.as_str())
+}
+
 fn validate_input(data_config: &TemplateData, input: &str) -> crate::Result<Value> {
     let input = input.trim();
 
@@ -167,19 +276,20 @@ fn validate_input(data_config: &TemplateData, input: &str) -> crate::Result<Valu
                 Ok(value)
             }
         }
+        TemplateDataType::Secret => Ok(Value::String(input.to_owned())),
         TemplateDataType::Number => {
             let value = input
                 .parse::<f64>()
                 .map_err(|_| crate::Error::new("Value must be a number"))
                 .and_then(|v| Number::from_f64(v).ok_or_else(|| crate::Error::new("Value must be a number")))
-                .map(|v| Value::Number(v))?;
+                .map(Value::Number)?;
 
             if let Some(ref values) = data_config.values {
                 let values = values
                     .iter()
                     .filter_map(|v| v.as_f64())
-                    .filter_map(|v| Number::from_f64(v))
-                    .map(|v| Value::Number(v))
+                    .filter_map(Number::from_f64)
+                    .map(Value::Number)
                     .collect::<Vec<Value>>();
 
                 if values.contains(&value) {
@@ -222,8 +332,8 @@ fn validate_input(data_config: &TemplateData, input: &str) -> crate::Result<Valu
             constants::RE_COMMA_SEPARATOR
                 .split(input)
                 .filter_map(|p| p.trim().parse::<f64>().ok())
-                .filter_map(|v| Number::from_f64(v))
-                .map(|p| Value::Number(p))
+                .filter_map(Number::from_f64)
+                .map(Value::Number)
                 .collect(),
         )),
         TemplateDataType::Semver => input
@@ -233,3 +343,56 @@ fn validate_input(data_config: &TemplateData, input: &str) -> crate::Result<Valu
             .wrap(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_config(data_type: TemplateDataType, required: bool) -> TemplateData {
+        TemplateData {
+            name: "foo".to_owned(),
+            data_type,
+            values: None,
+            message: String::new(),
+            required,
+            default_value: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_data_assignment() {
+        assert_eq!(
+            parse_data_assignment("license=MIT").unwrap(),
+            ("license".to_owned(), Value::String("MIT".to_owned()))
+        );
+        assert_eq!(
+            parse_data_assignment("cmd=a=b").unwrap(),
+            ("cmd".to_owned(), Value::String("a=b".to_owned()))
+        );
+        assert!(parse_data_assignment("license").is_err());
+        assert!(parse_data_assignment("=MIT").is_err());
+    }
+
+    #[test]
+    fn test_validate_value() {
+        let bool_config = data_config(TemplateDataType::Bool, false);
+        assert_eq!(
+            validate_value(&bool_config, &Value::Bool(true)).unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            validate_value(&bool_config, &Value::String("false".to_owned())).unwrap(),
+            Value::Bool(false)
+        );
+
+        let array_config = data_config(TemplateDataType::ArrayNumber, true);
+        assert_eq!(
+            validate_value(&array_config, &serde_json::json!([1, 2])).unwrap(),
+            serde_json::json!([1.0, 2.0])
+        );
+
+        let semver_config = data_config(TemplateDataType::Semver, true);
+        assert!(validate_value(&semver_config, &Value::String("1.x".to_owned())).is_err());
+        assert!(validate_value(&semver_config, &Value::String(String::new())).is_err());
+    }
+}
@@ -39,7 +39,7 @@ impl<E: std::error::Error + Send + Sync + 'static> ErrorExt for E {
     }
 
     fn context<C: Display + Send + Sync + 'static>(self, ctx: C) -> Error {
-        let msg = format!("{}: {}", ctx, self.to_string());
+        let msg = format!("{}: {}", ctx, self);
         Error { msg }
     }
 }
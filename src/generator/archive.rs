@@ -0,0 +1,169 @@
+use crate::constants;
+use crate::prelude::*;
+use crate::utils::{self, TempDir};
+use flate2::read::GzDecoder;
+use reqwest::blocking::Client;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+pub fn is_archive(location: &str) -> bool {
+    !archive_extension(location).is_empty()
+}
+
+/// Returns the archive extension of the location e.g. `.tar.gz`, it keeps the downloaded file recognizable.
+pub fn archive_extension(location: &str) -> &'static str {
+    let location = location_path(location).to_lowercase();
+
+    constants::TEMPLATE_ARCHIVE_EXTENSIONS
+        .iter()
+        .find(|ext| location.ends_with(*ext))
+        .copied()
+        .unwrap_or("")
+}
+
+/// The path of an archive URL without its query string and fragment e.g. of the signed URLs, the local paths are
+/// used as they are.
+fn location_path(location: &str) -> String {
+    match Url::parse(location) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => url.path().to_owned(),
+        _ => location.to_owned(),
+    }
+}
+
+pub fn download_archive(url: &str, dest_file: &Path) -> crate::Result<()> {
+    // The URL can have the credentials in it, the reqwest errors include the URL too.
+    let display_url = utils::redact_credentials(url);
+    let download_err = |err: reqwest::Error| {
+        crate::Error::new(format!(
+            "Couldn't download the template archive: {}: {}",
+            display_url,
+            utils::redact_credentials(err.to_string().as_str())
+        ))
+    };
+
+    let resp = Client::new()
+        .get(url)
+        .header(
+            "User-Agent",
+            format!("{} v{}", constants::APP_NAME, constants::APP_VERSION),
+        )
+        .send()
+        .map_err(download_err)?;
+
+    if !resp.status().is_success() {
+        return Err(crate::Error::new(format!(
+            "Couldn't download the template archive: {}: the server responded with {}",
+            display_url,
+            resp.status()
+        )));
+    }
+
+    let bytes = resp.bytes().map_err(download_err)?;
+
+    fs::write(dest_file, bytes.as_ref()).context(format!(
+        "Couldn't save the template archive: {}",
+        dest_file.to_str().unwrap_or("")
+    ))
+}
+
+/// Downloads the archive and unpacks it into the dest dir.
+pub fn download_and_unpack_archive(url: &str, dest_dir: &Path) -> crate::Result<()> {
+    let download_dir = TempDir::new().context("Couldn't create a temporary folder to download the template")?;
+    let archive_path = download_dir.path().join(format!("template{}", archive_extension(url)));
+
+    download_archive(url, archive_path.as_path())?;
+    unpack_archive(archive_path.as_path(), dest_dir)
+}
+
+/// Unpacks a `.tar.gz`, `.tgz` or `.zip` archive into the dest dir.
+pub fn unpack_archive(archive_path: &Path, dest_dir: &Path) -> crate::Result<()> {
+    let archive_path_str = archive_path.to_str().unwrap_or("");
+    let file = File::open(archive_path).context(format!("Couldn't open the template archive: {}", archive_path_str))?;
+
+    if archive_extension(archive_path_str) == constants::TEMPLATE_ARCHIVE_EXTENSION_ZIP {
+        zip::ZipArchive::new(file)
+            .and_then(|mut archive| archive.extract(dest_dir))
+            .context(format!("Couldn't unpack the template archive: {}", archive_path_str))?;
+    } else {
+        tar::Archive::new(GzDecoder::new(file))
+            .unpack(dest_dir)
+            .context(format!("Couldn't unpack the template archive: {}", archive_path_str))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the template root of an unpacked archive, which is its single top level dir if it has one e.g.
+/// `repo-1.2.0/` in the release tarballs.
+pub fn archive_root(dest_dir: &Path) -> crate::Result<PathBuf> {
+    let entries = fs::read_dir(dest_dir)
+        .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+        .context("Couldn't read the unpacked template archive")?;
+
+    match entries.as_slice() {
+        [entry] if entry.path().is_dir() => Ok(entry.path()),
+        _ => Ok(dest_dir.to_path_buf()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn test_is_archive() {
+        assert!(is_archive("../template.tar.gz"));
+        assert!(is_archive("https://example.com/releases/Template.ZIP"));
+        assert!(is_archive("https://example.com/t.tar.gz?token=abc&expires=123"));
+        assert!(is_archive("https://example.com/t.tgz#readme"));
+        assert!(!is_archive("https://example.com/download?file=t.tar.gz"));
+        assert!(!is_archive("https://github.com/john/template.git"));
+        assert_eq!(archive_extension("https://example.com/t.tar.gz?sig=x"), ".tar.gz");
+    }
+
+    #[test]
+    fn test_unpack_archive() {
+        let dir = TempDir::new().unwrap();
+
+        let tar_path = dir.path().join("template.tar.gz");
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            File::create(tar_path.as_path()).unwrap(),
+            Compression::default(),
+        ));
+        let content = b"version: 1.0.0";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "template-1.0.0/boilerplato.yml", &content[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let tar_dest = dir.path().join("tar");
+        fs::create_dir(tar_dest.as_path()).unwrap();
+        unpack_archive(tar_path.as_path(), tar_dest.as_path()).unwrap();
+        let root = archive_root(tar_dest.as_path()).unwrap();
+        assert_eq!(root, tar_dest.join("template-1.0.0"));
+        assert!(root.join("boilerplato.yml").is_file());
+
+        let zip_path = dir.path().join("template.zip");
+        let mut writer = zip::ZipWriter::new(File::create(zip_path.as_path()).unwrap());
+        writer.start_file("boilerplato.yml", Default::default()).unwrap();
+        writer.write_all(content).unwrap();
+        writer.start_file("src/main.rs", Default::default()).unwrap();
+        writer.finish().unwrap();
+
+        let zip_dest = dir.path().join("zip");
+        fs::create_dir(zip_dest.as_path()).unwrap();
+        unpack_archive(zip_path.as_path(), zip_dest.as_path()).unwrap();
+        let root = archive_root(zip_dest.as_path()).unwrap();
+        assert_eq!(root, zip_dest);
+        assert!(root.join("boilerplato.yml").is_file());
+        assert!(root.join("src/main.rs").is_file());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::constants;
+use crate::data_prompts;
+use crate::prelude::*;
+use crate::types::ConflictStrategy;
+use colored::*;
+use similar::{ChangeTag, TextDiff};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::str;
+
+pub fn ask_conflict_strategy(rel_path: &Path, existing: &[u8], generated: &[u8]) -> crate::Result<ConflictStrategy> {
+    let rel_path_str = rel_path.to_str().unwrap_or("");
+
+    if !data_prompts::is_interactive() {
+        return Err(crate::Error::new(format!(
+            "The project file already exists and the stdin is not a terminal to ask what to do with it: {}",
+            rel_path_str
+        )));
+    }
+
+    print_diff(rel_path, existing, generated);
+
+    let stdout = io::stdout();
+    let mut w_handle = stdout.lock();
+
+    loop {
+        w_handle.write_fmt(format_args!("{}", "? ".cyan())).wrap()?;
+        w_handle
+            .write_fmt(format_args!(
+                "{}",
+                format!(
+                    "`{}` already exists, [o]verwrite, [s]kip or [b]ackup to {}{}:",
+                    rel_path_str,
+                    rel_path_str,
+                    constants::CONFLICT_BACKUP_FILE_EXTENSION
+                )
+                .as_str()
+                .bold()
+            ))
+            .wrap()?;
+        w_handle.write_fmt(format_args!("{}", " › ".bright_black())).wrap()?;
+        w_handle.flush().wrap()?;
+
+        let mut line = String::with_capacity(10);
+        if io::stdin().lock().read_line(&mut line).wrap()? == 0 {
+            return Err(crate::Error::new(format!(
+                "Reached the end of input while asking what to do with the existing file: {}",
+                rel_path_str
+            )));
+        }
+
+        match line.trim().to_lowercase().as_str() {
+            "o" | constants::CONFLICT_STRATEGY_OVERWRITE => return Ok(ConflictStrategy::Overwrite),
+            "s" | constants::CONFLICT_STRATEGY_SKIP => return Ok(ConflictStrategy::Skip),
+            "b" | constants::CONFLICT_STRATEGY_BACKUP => return Ok(ConflictStrategy::Backup),
+            _ => {
+                w_handle.write_fmt(format_args!("› ")).wrap()?;
+                w_handle
+                    .write_fmt(format_args!("{}", "Value must be one of: o, s, b".red().italic()))
+                    .wrap()?;
+                w_handle.write_fmt(format_args!("\n")).wrap()?;
+                w_handle.flush().wrap()?;
+            }
+        }
+    }
+}
+
+/// The path an existing project file is renamed to by the backup strategy, e.g. `README.md.old`.
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut backup_path = path.as_os_str().to_owned();
+    backup_path.push(constants::CONFLICT_BACKUP_FILE_EXTENSION);
+    PathBuf::from(backup_path)
+}
+
+pub fn print_diff(rel_path: &Path, existing: &[u8], generated: &[u8]) {
+    let rel_path_str = rel_path.to_str().unwrap_or("");
+
+    let (existing, generated) = match (str::from_utf8(existing), str::from_utf8(generated)) {
+        (Ok(existing), Ok(generated)) => (existing, generated),
+        _ => {
+            println!("Binary files differ: {}", rel_path_str);
+            return;
+        }
+    };
+
+    let diff = TextDiff::from_lines(existing, generated);
+
+    println!("{}", format!("--- a/{}", rel_path_str).bold());
+    println!("{}", format!("+++ b/{}", rel_path_str).bold());
+
+    for hunk in diff.unified_diff().context_radius(3).iter_hunks() {
+        println!("{}", hunk.header().to_string().cyan());
+
+        for change in hunk.iter_changes() {
+            let line = format!("{}{}", change.tag(), change.value().trim_end_matches('\n'));
+            match change.tag() {
+                ChangeTag::Delete => println!("{}", line.red()),
+                ChangeTag::Insert => println!("{}", line.green()),
+                ChangeTag::Equal => println!("{}", line),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_path() {
+        assert_eq!(backup_path(Path::new("README.md")), PathBuf::from("README.md.old"));
+        assert_eq!(
+            backup_path(Path::new("/app/src/main.rs")),
+            PathBuf::from("/app/src/main.rs.old")
+        );
+        assert_eq!(backup_path(Path::new("/app/.env")), PathBuf::from("/app/.env.old"));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::config::UserConfig;
+use crate::constants;
+use git2::{Config, Cred, CredentialType, ErrorClass, ErrorCode};
+use std::env;
+use std::path::PathBuf;
+use url::Url;
+
+/// Supplies the credentials of a template repo to git. As git asks again after every rejected attempt, each
+/// kind of credentials is offered only once.
+pub struct CredentialProvider {
+    token: Option<(String, String)>,
+    tried_token: bool,
+    tried_helper: bool,
+    tried_ssh_agent: bool,
+    ssh_keys: Vec<PathBuf>,
+    tried_username: bool,
+}
+
+impl CredentialProvider {
+    pub fn new(repo_url: &str) -> CredentialProvider {
+        let ssh_dir = env::var_os("HOME").map(|home| PathBuf::from(home).join(".ssh"));
+
+        CredentialProvider {
+            token: find_token(repo_url),
+            tried_token: false,
+            tried_helper: false,
+            tried_ssh_agent: false,
+            ssh_keys: ["id_ed25519", "id_ecdsa", "id_rsa"]
+                .iter()
+                .filter_map(|name| ssh_dir.as_ref().map(|dir| dir.join(name)))
+                .filter(|path| path.is_file())
+                .collect(),
+            tried_username: false,
+        }
+    }
+
+    /// The credentials callback, the tokens are tried first, then the git credential helpers for HTTPS and
+    /// the SSH agent and the default keys for SSH.
+    pub fn credentials(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            if !self.tried_token {
+                self.tried_token = true;
+
+                if let Some((ref username, ref token)) = self.token {
+                    let username = username_from_url.unwrap_or(username);
+                    return Cred::userpass_plaintext(username, token);
+                }
+            }
+
+            if !self.tried_helper {
+                self.tried_helper = true;
+
+                let helper_cred =
+                    Config::open_default().and_then(|config| Cred::credential_helper(&config, url, username_from_url));
+                if let Ok(cred) = helper_cred {
+                    return Ok(cred);
+                }
+            }
+        }
+
+        let ssh_username = username_from_url.unwrap_or(constants::GIT_SSH_DEFAULT_USERNAME);
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            if !self.tried_ssh_agent {
+                self.tried_ssh_agent = true;
+                return Cred::ssh_key_from_agent(ssh_username);
+            }
+
+            if !self.ssh_keys.is_empty() {
+                let key = self.ssh_keys.remove(0);
+                return Cred::ssh_key(ssh_username, None, key.as_path(), None);
+            }
+        }
+
+        if allowed.contains(CredentialType::USERNAME) && !self.tried_username {
+            self.tried_username = true;
+            return Cred::username(ssh_username);
+        }
+
+        Err(git2::Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Net,
+            "no valid credentials found for the repo",
+        ))
+    }
+}
+
+/// Finds the username and the token for the repo host, the host credentials in the user config take precedence
+/// over the host specific token env vars e.g. `$GITHUB_TOKEN`, and then over `$BOILERPLATO_GIT_TOKEN`. Only the
+/// system and the user configs are read, as a project's `.boilerplatorc` can't decide where the tokens go.
+fn find_token(repo_url: &str) -> Option<(String, String)> {
+    let config = UserConfig::load_trusted().unwrap_or_default();
+    find_token_in(repo_url, &config, &env_token)
+}
+
+/// The env tokens are sent only over HTTPS, and the generic `$BOILERPLATO_GIT_TOKEN` isn't bound to a host, so it's
+/// sent only to the hosts known by the config.
+fn find_token_in(
+    repo_url: &str,
+    config: &UserConfig,
+    env_token: &dyn Fn(&str) -> Option<String>,
+) -> Option<(String, String)> {
+    let url = Url::parse(repo_url).ok()?;
+    let host = url.host_str()?;
+
+    let host_credentials = config.host_credentials(host);
+    let host_username = host_credentials.and_then(|creds| creds.username.clone());
+
+    if let Some(token) = host_credentials.and_then(|creds| creds.token()) {
+        let username = host_username.unwrap_or_else(|| constants::GIT_TOKEN_DEFAULT_USERNAME.to_owned());
+        return Some((username, token));
+    }
+
+    if url.scheme() != "https" {
+        return None;
+    }
+
+    let host_token_var = constants::GIT_HOST_TOKEN_ENV_VARS
+        .iter()
+        .find(|(token_host, _, _)| *token_host == host);
+
+    let (token, default_username) = host_token_var
+        .and_then(|(_, var, username)| env_token(var).map(|token| (token, *username)))
+        .or_else(|| {
+            if !config.is_known_host(host) {
+                return None;
+            }
+
+            env_token(constants::GIT_TOKEN_ENV_VAR).map(|token| {
+                let username = host_token_var
+                    .map(|(_, _, username)| *username)
+                    .unwrap_or(constants::GIT_TOKEN_DEFAULT_USERNAME);
+                (token, username)
+            })
+        })?;
+
+    Some((host_username.unwrap_or_else(|| default_username.to_owned()), token))
+}
+
+fn env_token(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|token| !token.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_token_in() {
+        let config = UserConfig::parse(
+            "\
+forges:
+  corp: https://git.corp.example/
+",
+        )
+        .unwrap();
+        let env_token = |name: &str| {
+            if name == constants::GIT_TOKEN_ENV_VAR {
+                Some("secret".to_owned())
+            } else {
+                None
+            }
+        };
+        let github_env_token = |name: &str| {
+            if name == constants::GITHUB_TOKEN_ENV_VAR {
+                Some("gh-secret".to_owned())
+            } else {
+                None
+            }
+        };
+
+        assert_eq!(
+            find_token_in("https://git.corp.example/team/repo.git", &config, &env_token),
+            Some(("oauth2".to_owned(), "secret".to_owned()))
+        );
+        assert_eq!(
+            find_token_in("https://github.com/john/repo.git", &config, &env_token),
+            Some(("x-access-token".to_owned(), "secret".to_owned()))
+        );
+        assert_eq!(
+            find_token_in("http://git.corp.example/team/repo.git", &config, &env_token),
+            None
+        );
+        assert_eq!(
+            find_token_in("https://evil.example/team/repo.git", &config, &env_token),
+            None
+        );
+        assert_eq!(
+            find_token_in("https://github.com/john/repo.git", &config, &github_env_token),
+            Some(("x-access-token".to_owned(), "gh-secret".to_owned()))
+        );
+        assert_eq!(
+            find_token_in("http://github.com/john/repo.git", &config, &github_env_token),
+            None
+        );
+    }
+}
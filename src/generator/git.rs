@@ -0,0 +1,616 @@
+use crate::constants;
+use crate::generator::credentials::CredentialProvider;
+use crate::prelude::*;
+use crate::utils;
+use git2::build::CheckoutBuilder;
+use git2::{
+    Commit, Direction, ErrorClass, ErrorCode, FetchOptions, Oid, Remote, RemoteCallbacks, Repository,
+    SubmoduleUpdateOptions,
+};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// The git operation failures, the auth and the missing repo or ref are told apart to be reported clearly.
+#[derive(Debug)]
+pub enum GitError {
+    AuthFailed { url: String },
+    RepoNotFound { url: String },
+    RefNotFound { git_ref: String },
+    Other { action: String, err: git2::Error },
+}
+
+impl Display for GitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            GitError::AuthFailed { ref url } => format!(
+                "Authentication failed for the template repo: {}, provide a token through ${} or a git credential helper",
+                url,
+                constants::GIT_TOKEN_ENV_VAR
+            ),
+            GitError::RepoNotFound { ref url } => format!("The template repo not found: {}", url),
+            GitError::RefNotFound { ref git_ref } => format!("The template ref not found: {}", git_ref),
+            GitError::Other { ref action, ref err } => format!("{}: {}", action, err.message()),
+        };
+
+        // The URLs can have the credentials in them.
+        write!(f, "{}", utils::redact_credentials(msg.as_str()))
+    }
+}
+
+impl std::error::Error for GitError {}
+
+impl GitError {
+    fn other<A: Into<String>>(action: A) -> impl FnOnce(git2::Error) -> GitError {
+        let action = action.into();
+        move |err| GitError::Other { action, err }
+    }
+
+    /// Classifies a failure of talking to the remote repo.
+    fn remote(url: &str, action: &str, err: git2::Error) -> GitError {
+        let msg = err.message().to_lowercase();
+
+        if err.code() == ErrorCode::Auth || msg.contains("authentication") || msg.contains("401") {
+            GitError::AuthFailed { url: url.to_owned() }
+        } else if err.code() == ErrorCode::NotFound
+            || msg.contains("not found")
+            || msg.contains("404")
+            || msg.contains("does not exist")
+            || msg.contains("not a git repository")
+            || msg.contains("no such file or directory")
+        {
+            GitError::RepoNotFound { url: url.to_owned() }
+        } else {
+            GitError::Other {
+                action: action.to_owned(),
+                err,
+            }
+        }
+    }
+}
+
+/// Clones the repo into the dest dir. Only the latest commit of the ref, or of the default branch, is fetched
+/// from the remote repos, the local transport doesn't support the shallow fetches so the local repos are fetched
+/// fully. For the templates in a subdirectory only the subdirectory is checked out.
+pub fn clone(repo_url: &str, dest_dir: &Path, git_ref: Option<&str>, subdir: Option<&str>) -> Result<(), GitError> {
+    let repo = Repository::init(dest_dir).map_err(GitError::other("Couldn't create the template repo"))?;
+    repo.remote("origin", repo_url)
+        .map_err(GitError::other("Invalid template repo URL"))?;
+
+    let shallow = !is_local_url(repo_url);
+    let oid = match fetch(&repo, git_ref.unwrap_or("HEAD"), shallow)? {
+        Some(oid) => oid,
+        // An abbreviated commit hash can't be fetched by name, it's looked up in the fetched history.
+        None if git_ref.map(is_commit_hash).unwrap_or(false) => {
+            fetch_all(&repo)?;
+            find_commit(&repo, git_ref.unwrap_or(""))
+                .map(|commit| commit.id())
+                .ok_or_else(|| GitError::RefNotFound {
+                    git_ref: git_ref.unwrap_or("").to_owned(),
+                })?
+        }
+        None => {
+            return Err(GitError::RefNotFound {
+                git_ref: git_ref.unwrap_or("HEAD").to_owned(),
+            })
+        }
+    };
+
+    let commit = repo
+        .find_commit(oid)
+        .map_err(GitError::other("Couldn't find the fetched template commit"))?;
+    checkout_commit(&repo, &commit, subdir)
+}
+
+/// Fetches the latest version of the ref, or of the default branch, and checks it out.
+pub fn pull(repo_dir: &Path, git_ref: Option<&str>, subdir: Option<&str>) -> Result<(), GitError> {
+    let repo = open(repo_dir)?;
+    let shallow = repo.is_shallow();
+    let oid = fetch(&repo, git_ref.unwrap_or("HEAD"), shallow)?.ok_or_else(|| GitError::RefNotFound {
+        git_ref: git_ref.unwrap_or("HEAD").to_owned(),
+    })?;
+
+    let commit = repo
+        .find_commit(oid)
+        .map_err(GitError::other("Couldn't find the fetched template commit"))?;
+    checkout_commit(&repo, &commit, subdir)
+}
+
+/// Checks whether the ref is a full or an abbreviated commit hash which exists in the repo, such a ref always
+/// points to the same content.
+pub fn has_commit(repo_dir: &Path, git_ref: &str) -> bool {
+    is_commit_hash(git_ref)
+        && Repository::open(repo_dir)
+            .ok()
+            .and_then(|repo| {
+                repo.revparse_single(git_ref)
+                    .and_then(|object| object.peel_to_commit())
+                    .ok()
+                    .map(|commit| commit.id().to_string().starts_with(&git_ref.to_lowercase()))
+            })
+            .unwrap_or(false)
+}
+
+/// Lists the remote tags as the ref names and their commits, the annotated tags are listed once more with
+/// the `^{}` suffix pointing to the tagged commit.
+pub fn ls_remote_tags(repo_url: &str) -> Result<Vec<(String, String)>, GitError> {
+    let mut remote = Remote::create_detached(repo_url).map_err(GitError::other("Invalid template repo URL"))?;
+
+    let connection = remote
+        .connect_auth(Direction::Fetch, Some(remote_callbacks(repo_url)), None)
+        .map_err(|err| GitError::remote(repo_url, "Couldn't list the tags of the template repo", err))?;
+
+    let heads = connection
+        .list()
+        .map_err(|err| GitError::remote(repo_url, "Couldn't list the tags of the template repo", err))?;
+
+    Ok(heads
+        .iter()
+        .filter(|head| head.name().starts_with("refs/tags/"))
+        .map(|head| (head.name().to_owned(), head.oid().to_string()))
+        .collect())
+}
+
+/// Checks whether the remote repo exists. The repos which reject the credentials fail with `AuthFailed`, as the git
+/// hosts e.g. GitHub ask for them for both the private and the missing repos.
+pub fn remote_exists(repo_url: &str) -> Result<bool, GitError> {
+    let mut remote = Remote::create_detached(repo_url).map_err(GitError::other("Invalid template repo URL"))?;
+
+    let result = remote
+        .connect_auth(Direction::Fetch, Some(remote_callbacks(repo_url)), None)
+        .map(|_| ());
+
+    match result.map_err(|err| GitError::remote(repo_url, "Couldn't connect with the template repo", err)) {
+        Ok(_) => Ok(true),
+        Err(GitError::RepoNotFound { .. }) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// The commit checked out in the repo containing the dir.
+pub fn head_commit(dir: &Path) -> Option<String> {
+    let repo = Repository::discover(dir).ok()?;
+    let commit = repo.head().and_then(|head| head.peel_to_commit()).ok()?;
+
+    Some(commit.id().to_string())
+}
+
+/// A tag pointing to the commit checked out in the repo containing the dir.
+pub fn head_tag(dir: &Path) -> Option<String> {
+    let repo = Repository::discover(dir).ok()?;
+    let head = repo.head().and_then(|head| head.peel_to_commit()).ok()?.id();
+
+    let tags = repo.tag_names(None).ok()?;
+    let mut tags = tags
+        .iter()
+        .flatten()
+        .filter(|tag| {
+            repo.revparse_single(format!("refs/tags/{}", tag).as_str())
+                .and_then(|object| object.peel_to_commit())
+                .map(|commit| commit.id() == head)
+                .unwrap_or(false)
+        })
+        .collect::<Vec<&str>>();
+
+    tags.sort_unstable();
+    tags.first().map(|tag| (*tag).to_owned())
+}
+
+/// Initializes and updates the submodules recursively, for a template in a subdirectory only the submodules
+/// inside the subdirectory are updated.
+pub fn update_submodules(repo_dir: &Path, subdir: Option<&str>) -> Result<(), GitError> {
+    let repo = open(repo_dir)?;
+    update_submodules_of(&repo, subdir.map(|subdir| subdir.trim_matches('/')))
+}
+
+/// Checks whether the repo, or its subdirectory, tracks any file with git LFS.
+pub fn uses_lfs(repo_dir: &Path, subdir: Option<&str>) -> bool {
+    let mut attributes_files = vec![repo_dir.join(constants::GIT_ATTRIBUTES_FILE_NAME)];
+    if let Some(subdir) = subdir {
+        attributes_files.push(repo_dir.join(subdir).join(constants::GIT_ATTRIBUTES_FILE_NAME));
+    }
+
+    attributes_files
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .any(|attributes| attributes.contains("filter=lfs"))
+}
+
+/// Replaces the LFS pointer files with their content through the `git lfs` command, returns `false` if git LFS
+/// is not installed.
+pub fn lfs_pull(repo_dir: &Path, subdir: Option<&str>) -> crate::Result<bool> {
+    let installed = Command::new("git")
+        .args(["lfs", "version"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+
+    if !installed {
+        return Ok(false);
+    }
+
+    let mut command = Command::new("git");
+    command.args(["lfs", "pull"]).current_dir(repo_dir);
+
+    if let Some(subdir) = subdir {
+        command.args(["--include", format!("{}/**", subdir.trim_matches('/')).as_str()]);
+    }
+
+    let output = command.output().context("Couldn't run 'git lfs pull'")?;
+    if !output.status.success() {
+        return Err(crate::Error::new(format!(
+            "Couldn't fetch the template LFS files: {}",
+            utils::redact_credentials(String::from_utf8_lossy(&output.stderr).trim())
+        )));
+    }
+
+    Ok(true)
+}
+
+pub fn init(dir: &Path) -> Result<(), GitError> {
+    Repository::init(dir)
+        .map(|_| ())
+        .map_err(GitError::other("Failed to initialize git to the project dir"))
+}
+
+fn open(repo_dir: &Path) -> Result<Repository, GitError> {
+    Repository::open(repo_dir).map_err(GitError::other(format!(
+        "Couldn't open the template repo: {}",
+        repo_dir.to_str().unwrap_or("")
+    )))
+}
+
+/// Fetches a ref from the origin and returns the fetched commit, `None` if the remote doesn't have the ref. A
+/// short ref is tried as a branch, as a tag and as a commit. A shallow fetch gets only the commit of the ref.
+fn fetch(repo: &Repository, git_ref: &str, shallow: bool) -> Result<Option<Oid>, GitError> {
+    let mut remote = repo
+        .find_remote("origin")
+        .map_err(GitError::other("Couldn't find the origin of the template repo"))?;
+    let url = remote.url().unwrap_or("").to_owned();
+
+    // The refspecs and the local refs they are fetched into.
+    let refspecs = if git_ref == "HEAD" || git_ref.starts_with("refs/") {
+        vec![(git_ref.to_owned(), "FETCH_HEAD".to_owned())]
+    } else {
+        vec![
+            (
+                format!("+refs/heads/{0}:refs/remotes/origin/{0}", git_ref),
+                format!("refs/remotes/origin/{}", git_ref),
+            ),
+            (
+                format!("+refs/tags/{0}:refs/tags/{0}", git_ref),
+                format!("refs/tags/{}", git_ref),
+            ),
+            (git_ref.to_owned(), git_ref.to_owned()),
+        ]
+    };
+
+    for (refspec, local_ref) in refspecs {
+        let mut fetch_options = fetch_options(url.as_str());
+        if shallow {
+            fetch_options.depth(1);
+        }
+
+        // The fetch of a ref missing in the remote can succeed without fetching anything.
+        match remote.fetch(&[refspec.as_str()], Some(&mut fetch_options), None) {
+            Ok(_) => {
+                let fetched = repo
+                    .revparse_single(local_ref.as_str())
+                    .and_then(|object| object.peel_to_commit())
+                    .map(|commit| commit.id());
+
+                if let Ok(oid) = fetched {
+                    return Ok(Some(oid));
+                }
+            }
+            Err(err) if is_missing_ref(&err) => continue,
+            Err(err) => return Err(GitError::remote(url.as_str(), "Couldn't fetch the template repo", err)),
+        }
+    }
+
+    Ok(None)
+}
+
+/// Fetches the whole history of all the branches and the tags.
+fn fetch_all(repo: &Repository) -> Result<(), GitError> {
+    let mut remote = repo
+        .find_remote("origin")
+        .map_err(GitError::other("Couldn't find the origin of the template repo"))?;
+    let url = remote.url().unwrap_or("").to_owned();
+
+    remote
+        .fetch(
+            &["+refs/heads/*:refs/remotes/origin/*", "+refs/tags/*:refs/tags/*"],
+            Some(&mut fetch_options(url.as_str())),
+            None,
+        )
+        .map_err(|err| GitError::remote(url.as_str(), "Couldn't fetch the template repo", err))
+}
+
+fn is_commit_hash(git_ref: &str) -> bool {
+    git_ref.len() >= 7 && git_ref.len() <= 40 && git_ref.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_missing_ref(err: &git2::Error) -> bool {
+    err.class() == ErrorClass::Reference
+        || err.message().contains("no match for")
+        || err.message().contains("not our ref")
+        || err.message().contains("not found")
+}
+
+fn remote_callbacks<'a>(repo_url: &str) -> RemoteCallbacks<'a> {
+    let mut provider = CredentialProvider::new(repo_url);
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username_from_url, allowed| provider.credentials(url, username_from_url, allowed));
+    callbacks
+}
+
+fn fetch_options<'a>(repo_url: &str) -> FetchOptions<'a> {
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(remote_callbacks(repo_url));
+    fetch_options
+}
+
+fn update_submodules_of(repo: &Repository, subdir: Option<&str>) -> Result<(), GitError> {
+    let submodules = repo
+        .submodules()
+        .map_err(GitError::other("Couldn't read the template submodules"))?;
+
+    for mut submodule in submodules {
+        if let Some(subdir) = subdir {
+            if !submodule.path().starts_with(subdir) {
+                continue;
+            }
+        }
+
+        let url = submodule.url().unwrap_or("").to_owned();
+        let mut update_options = SubmoduleUpdateOptions::new();
+        update_options.fetch(fetch_options(url.as_str()));
+
+        submodule
+            .update(true, Some(&mut update_options))
+            .map_err(|err| GitError::remote(url.as_str(), "Couldn't update the template submodule", err))?;
+
+        let submodule_repo = submodule.open().map_err(GitError::other(format!(
+            "Couldn't open the template submodule: {}",
+            submodule.path().to_str().unwrap_or("")
+        )))?;
+        update_submodules_of(&submodule_repo, None)?;
+    }
+
+    Ok(())
+}
+
+/// Resolves a ref as a tag, a remote branch, a local branch or a commit hash.
+fn find_commit<'r>(repo: &'r Repository, git_ref: &str) -> Option<Commit<'r>> {
+    [
+        format!("refs/tags/{}", git_ref),
+        format!("refs/remotes/origin/{}", git_ref),
+        git_ref.to_owned(),
+    ]
+    .iter()
+    .find_map(|spec| {
+        repo.revparse_single(spec.as_str())
+            .and_then(|object| object.peel_to_commit())
+            .ok()
+    })
+}
+
+fn checkout_commit(repo: &Repository, commit: &Commit, subdir: Option<&str>) -> Result<(), GitError> {
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+
+    // The root git files describe the submodules and the LFS files of the subdirectory too.
+    if let Some(subdir) = subdir {
+        checkout
+            .path(subdir.trim_matches('/'))
+            .path(constants::TEMPLATE_GIT_MODULES_FILE_NAME)
+            .path(constants::GIT_ATTRIBUTES_FILE_NAME);
+    }
+
+    repo.checkout_tree(commit.as_object(), Some(&mut checkout))
+        .and_then(|_| repo.set_head_detached(commit.id()))
+        .map_err(GitError::other(format!(
+            "Couldn't checkout the template commit {}",
+            commit.id()
+        )))
+}
+
+/// The local transport doesn't support the shallow fetches.
+fn is_local_url(repo_url: &str) -> bool {
+    repo_url.starts_with("file:") || Path::new(repo_url).is_absolute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+    use git2::Signature;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use url::Url;
+
+    /// Commits the files onto the master branch of a bare repo.
+    fn commit(repo: &Repository, files: &[(&str, &str)], message: &str) -> Oid {
+        let parent = repo
+            .find_reference("refs/heads/master")
+            .and_then(|r| r.peel_to_commit())
+            .ok();
+
+        let mut builder = repo
+            .treebuilder(parent.as_ref().map(|c| c.tree().unwrap()).as_ref())
+            .unwrap();
+        for (dir, file, content) in files.iter().map(|(path, content)| {
+            let (dir, file) = path.split_once('/').unwrap();
+            (dir, file, content)
+        }) {
+            let existing = builder
+                .get(dir)
+                .unwrap()
+                .map(|entry| repo.find_tree(entry.id()).unwrap());
+            let mut sub_builder = repo.treebuilder(existing.as_ref()).unwrap();
+            sub_builder
+                .insert(file, repo.blob(content.as_bytes()).unwrap(), 0o100644)
+                .unwrap();
+            builder.insert(dir, sub_builder.write().unwrap(), 0o040000).unwrap();
+        }
+
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let signature = Signature::now("test", "test@example.com").unwrap();
+        let parents = parent.iter().collect::<Vec<&Commit>>();
+
+        repo.commit(
+            Some("refs/heads/master"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_update_submodules() {
+        let root = TempDir::new().unwrap();
+        let assets = Repository::init_bare(root.path().join("assets.git")).unwrap();
+        let assets_commit = commit(&assets, &[("img/logo.svg", "<svg/>")], "assets");
+        let assets_url = Url::from_file_path(root.path().join("assets.git")).unwrap().to_string();
+
+        let bare = Repository::init_bare(root.path().join("template.git")).unwrap();
+        bare.set_head("refs/heads/master").unwrap();
+        commit(&bare, &[("web/index.html", "v1")], "first");
+
+        let parent = bare
+            .find_reference("refs/heads/master")
+            .unwrap()
+            .peel_to_commit()
+            .unwrap();
+        let gitmodules = format!("[submodule \"assets\"]\n\tpath = web/assets\n\turl = {}\n", assets_url);
+
+        let mut web = bare
+            .treebuilder(Some(
+                &bare
+                    .find_tree(parent.tree().unwrap().get_name("web").unwrap().id())
+                    .unwrap(),
+            ))
+            .unwrap();
+        web.insert("assets", assets_commit, 0o160000).unwrap();
+        let mut tree = bare.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+        tree.insert("web", web.write().unwrap(), 0o040000).unwrap();
+        tree.insert(".gitmodules", bare.blob(gitmodules.as_bytes()).unwrap(), 0o100644)
+            .unwrap();
+
+        let signature = Signature::now("test", "test@example.com").unwrap();
+        bare.commit(
+            Some("refs/heads/master"),
+            &signature,
+            &signature,
+            "submodule",
+            &bare.find_tree(tree.write().unwrap()).unwrap(),
+            &[&parent],
+        )
+        .unwrap();
+
+        let url = Url::from_file_path(root.path().join("template.git"))
+            .unwrap()
+            .to_string();
+        let clone_dir = root.path().join("clone");
+        clone(url.as_str(), clone_dir.as_path(), None, Some("web")).unwrap();
+        assert!(!clone_dir.join("web/assets/img/logo.svg").exists());
+
+        update_submodules(clone_dir.as_path(), Some("web")).unwrap();
+        assert_eq!(
+            fs::read_to_string(clone_dir.join("web/assets/img/logo.svg")).unwrap(),
+            "<svg/>"
+        );
+        assert!(!uses_lfs(clone_dir.as_path(), Some("web")));
+    }
+
+    #[test]
+    fn test_clone_and_pull() {
+        let root = TempDir::new().unwrap();
+        let bare = Repository::init_bare(root.path().join("template.git")).unwrap();
+        bare.set_head("refs/heads/master").unwrap();
+
+        let first = commit(&bare, &[("web/index.html", "v1"), ("api/main.rs", "v1")], "first");
+        bare.tag_lightweight("v1.0.0", &bare.find_object(first, None).unwrap(), false)
+            .unwrap();
+        commit(&bare, &[("web/index.html", "v2"), ("api/main.rs", "v2")], "second");
+
+        let url = Url::from_file_path(root.path().join("template.git"))
+            .unwrap()
+            .to_string();
+
+        let latest = root.path().join("latest");
+        clone(url.as_str(), latest.as_path(), None, None).unwrap();
+        assert_eq!(fs::read_to_string(latest.join("web/index.html")).unwrap(), "v2");
+
+        let tagged = root.path().join("tagged");
+        clone(url.as_str(), tagged.as_path(), Some("v1.0.0"), Some("web")).unwrap();
+        assert_eq!(fs::read_to_string(tagged.join("web/index.html")).unwrap(), "v1");
+        assert!(!tagged.join("api").exists());
+        assert_eq!(head_tag(tagged.as_path()), Some("v1.0.0".to_owned()));
+        assert_eq!(head_commit(tagged.as_path()), Some(first.to_string()));
+        assert!(has_commit(tagged.as_path(), &first.to_string()[..7]));
+
+        let pinned = root.path().join("pinned");
+        clone(url.as_str(), pinned.as_path(), Some(&first.to_string()[..8]), None).unwrap();
+        assert_eq!(fs::read_to_string(pinned.join("api/main.rs")).unwrap(), "v1");
+
+        commit(&bare, &[("web/index.html", "v3")], "third");
+        pull(latest.as_path(), None, None).unwrap();
+        assert_eq!(fs::read_to_string(latest.join("web/index.html")).unwrap(), "v3");
+        pull(tagged.as_path(), Some("v1.0.0"), Some("web")).unwrap();
+        assert_eq!(fs::read_to_string(tagged.join("web/index.html")).unwrap(), "v1");
+
+        let tags = ls_remote_tags(url.as_str()).unwrap();
+        assert_eq!(tags, vec![("refs/tags/v1.0.0".to_owned(), first.to_string())]);
+
+        match clone(
+            url.as_str(),
+            root.path().join("missing-ref").as_path(),
+            Some("v9"),
+            None,
+        ) {
+            Err(GitError::RefNotFound { git_ref }) => assert_eq!(git_ref, "v9"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let missing_url = Url::from_file_path(root.path().join("missing.git"))
+            .unwrap()
+            .to_string();
+        match clone(missing_url.as_str(), root.path().join("missing").as_path(), None, None) {
+            Err(GitError::RepoNotFound { url }) => assert_eq!(url, missing_url),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        assert!(remote_exists(url.as_str()).unwrap());
+        assert!(!remote_exists(missing_url.as_str()).unwrap());
+    }
+
+    #[test]
+    fn test_remote_exists_auth_failed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/org/private.git", listener.local_addr().unwrap());
+
+        // Every request is rejected as unauthorized, like GitHub does for the private repos.
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buf = [0; 4096];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(
+                    b"HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Basic realm=\"git\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                );
+            }
+        });
+
+        match remote_exists(url.as_str()) {
+            Err(GitError::AuthFailed { url: failed_url }) => assert_eq!(failed_url, url),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
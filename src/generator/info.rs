@@ -0,0 +1,241 @@
+use crate::constants;
+use crate::generator::post_generator::group_post_generate_commands;
+use crate::generator::progress::Progress;
+use crate::generator::project_template::ProjectTemplate;
+use crate::prelude::*;
+use crate::types::{ConflictStrategy, OutputFormat, TemplateConfig, TemplateData};
+use crate::utils::{self, json_val_to_actual_str};
+use colored::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The template metadata shown by the `info` subcommand.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateInfo {
+    pub template: String,
+    pub version: String,
+    pub engine: String,
+    pub path: String,
+    pub extension: String,
+    pub submodules: bool,
+    pub data: Vec<TemplateData>,
+    pub files: Vec<FileRule>,
+    pub conflicts: BTreeMap<String, ConflictStrategy>,
+    /// The post generate commands keyed by the OS e.g. `linux`, `macos + linux` or `all`.
+    pub post_generate: BTreeMap<String, Vec<String>>,
+}
+
+/// A conditional file of the template, it's generated only if the check passes.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRule {
+    pub path: String,
+    pub check: Option<String>,
+    pub new_name: Option<String>,
+}
+
+impl TemplateInfo {
+    pub fn new(template: String, config: TemplateConfig) -> TemplateInfo {
+        let mut files = config
+            .files
+            .iter()
+            .flatten()
+            .map(|(path, val)| FileRule {
+                path: path.to_owned(),
+                check: match val {
+                    Value::String(check) => Some(check.to_owned()),
+                    _ => val.get("check").and_then(|v| v.as_str()).map(|s| s.to_owned()),
+                },
+                new_name: val.get("newName").and_then(|v| v.as_str()).map(|s| s.to_owned()),
+            })
+            .collect::<Vec<FileRule>>();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        TemplateInfo {
+            template,
+            version: config.version,
+            engine: config.template.engine,
+            path: utils::or(
+                config.template.path.is_empty(),
+                constants::TEMPLATE_DEFAULT_TEMPLATE_PATH.to_owned(),
+                config.template.path,
+            ),
+            extension: utils::or(
+                config.template.extension.is_empty(),
+                constants::TEMPLATE_DEFAULT_FILE_EXTENSION.to_owned(),
+                config.template.extension,
+            ),
+            submodules: config.template.submodules,
+            data: config.data,
+            files,
+            conflicts: config.conflicts.unwrap_or_default().into_iter().collect(),
+            post_generate: config
+                .post_generate
+                .as_ref()
+                .map(group_post_generate_commands)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Fetches the template and prints its config without generating anything.
+pub fn show_template_info(template: ProjectTemplate, offline: bool, format: OutputFormat) -> crate::Result<()> {
+    let template_dir = template.fetch(None, offline, Progress::Stderr)?;
+    let config = template.extract_template_config(template_dir.path())?;
+    let info = TemplateInfo::new(utils::redact_credentials(template.to_string().as_str()), config);
+
+    if format == OutputFormat::Json {
+        let json = serde_json::to_string_pretty(&info).context("Couldn't serialize the template info")?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    print_template_info(&info);
+    Ok(())
+}
+
+fn print_template_info(info: &TemplateInfo) {
+    println!();
+    println!(
+        "   {} {}",
+        info.template.as_str().green(),
+        format!("v{}", info.version).bright_black()
+    );
+    println!(
+        "   {}",
+        format!(
+            "engine {}  path {}  extension {}{}",
+            info.engine,
+            info.path,
+            info.extension,
+            utils::or(info.submodules, "  with submodules", "")
+        )
+        .bright_black()
+    );
+
+    println!();
+    println!("   {}", "Data".bold());
+    if info.data.is_empty() {
+        println!("     {}", "None".bright_black());
+    }
+    for data in info.data.iter() {
+        let data_type = serialized_name(&data.data_type);
+
+        println!(
+            "     {} {}{}",
+            data.name.as_str().cyan(),
+            data_type.bright_black(),
+            utils::or(data.required, " required".yellow(), "".normal())
+        );
+        if !data.message.is_empty() {
+            println!("       {}", data.message.trim());
+        }
+        if let Some(ref default_value) = data.default_value {
+            println!("       default: {}", json_val_to_actual_str(default_value));
+        }
+        if let Some(ref values) = data.values {
+            let values = values.iter().map(json_val_to_actual_str).collect::<Vec<String>>();
+            println!("       values: {}", values.join(", "));
+        }
+    }
+
+    if !info.files.is_empty() {
+        println!();
+        println!("   {}", "Files".bold());
+        for rule in info.files.iter() {
+            println!(
+                "     {}{}{}",
+                rule.path.as_str().cyan(),
+                rule.check
+                    .as_ref()
+                    .map(|check| format!(" if {}", check))
+                    .unwrap_or_default(),
+                rule.new_name
+                    .as_ref()
+                    .map(|new_name| format!(" renamed to {}", new_name))
+                    .unwrap_or_default()
+            );
+        }
+    }
+
+    if !info.conflicts.is_empty() {
+        println!();
+        println!("   {}", "Conflicts".bold());
+        for (path, strategy) in info.conflicts.iter() {
+            println!("     {} {}", path.as_str().cyan(), serialized_name(strategy));
+        }
+    }
+
+    if !info.post_generate.is_empty() {
+        println!();
+        println!("   {}", "Post generate".bold());
+        for (os, commands) in info.post_generate.iter() {
+            println!("     {}", os.as_str().cyan());
+            for command in commands {
+                println!("       $ {}", command);
+            }
+        }
+    }
+
+    println!();
+}
+
+/// The name of an enum variant as in the template config e.g. `array[string]`.
+fn serialized_name<T: Serialize>(val: &T) -> String {
+    serde_json::to_value(val)
+        .ok()
+        .and_then(|val| val.as_str().map(|s| s.to_owned()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ConfigFileType;
+
+    #[test]
+    fn test_template_info() {
+        let config = TemplateConfig::parse(
+            r#"
+version: 1.0.0
+template:
+  engine: handlebars
+data:
+  - name: license
+    type: string
+    required: false
+    values: [MIT, Apache-2.0]
+    defaultValue: MIT
+files:
+  LICENSE: "{{license}}"
+  src/cli.rs:
+    check: "{{cli}}"
+    newName: src/main.rs
+postGenerate:
+  all: cargo fmt
+  macos + linux: [chmod +x run.sh, ./run.sh]
+"#,
+            ConfigFileType::YAML,
+        )
+        .unwrap();
+
+        let info = TemplateInfo::new("john/rust-template".to_owned(), config);
+        assert_eq!(info.path, constants::TEMPLATE_DEFAULT_TEMPLATE_PATH);
+        assert_eq!(info.data[0].values.as_ref().unwrap().len(), 2);
+        assert_eq!(
+            info.files[1],
+            FileRule {
+                path: "src/cli.rs".to_owned(),
+                check: Some("{{cli}}".to_owned()),
+                new_name: Some("src/main.rs".to_owned()),
+            }
+        );
+        assert_eq!(info.post_generate["all"], vec!["cargo fmt"]);
+        assert_eq!(info.post_generate["macos + linux"], vec!["chmod +x run.sh", "./run.sh"]);
+
+        let groups = group_post_generate_commands(&Value::String("npm install".to_owned()));
+        assert_eq!(groups[constants::TEMPLATE_OS_FLAG_ALL], vec!["npm install"]);
+    }
+}
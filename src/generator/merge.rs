@@ -0,0 +1,146 @@
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+const CONFLICT_MARKER_OURS: &str = "<<<<<<< project";
+const CONFLICT_MARKER_SEPARATOR: &str = "=======";
+const CONFLICT_MARKER_THEIRS: &str = ">>>>>>> template";
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct MergeResult {
+    pub text: String,
+    pub conflicts: usize,
+}
+
+/// Merges the changes made on `base` in `ours` and in `theirs` line by line, the colliding changes are
+/// written with git style conflict markers.
+pub fn merge_text(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let base_lines = split_lines(base);
+    let our_lines = split_lines(ours);
+    let their_lines = split_lines(theirs);
+
+    let our_matches = match_lines(&base_lines, &our_lines);
+    let their_matches = match_lines(&base_lines, &their_lines);
+
+    let mut text = String::with_capacity(ours.len().max(theirs.len()));
+    let mut conflicts = 0;
+    let (mut i, mut j, mut k) = (0, 0, 0);
+
+    loop {
+        if i < base_lines.len() && our_matches[i] == Some(j) && their_matches[i] == Some(k) {
+            text.push_str(base_lines[i]);
+            i += 1;
+            j += 1;
+            k += 1;
+            continue;
+        }
+
+        // Find the next base line which is kept by both sides, everything before it is a changed chunk.
+        let stable = (i..base_lines.len()).find_map(|b| match (our_matches[b], their_matches[b]) {
+            (Some(o), Some(t)) if o >= j && t >= k => Some((b, o, t)),
+            _ => None,
+        });
+        let (b, o, t) = stable.unwrap_or((base_lines.len(), our_lines.len(), their_lines.len()));
+
+        let base_chunk = &base_lines[i..b];
+        let our_chunk = &our_lines[j..o];
+        let their_chunk = &their_lines[k..t];
+
+        if our_chunk == base_chunk || our_chunk == their_chunk {
+            their_chunk.iter().for_each(|line| text.push_str(line));
+        } else if their_chunk == base_chunk {
+            our_chunk.iter().for_each(|line| text.push_str(line));
+        } else {
+            conflicts += 1;
+            push_conflict_section(&mut text, CONFLICT_MARKER_OURS, our_chunk);
+            push_conflict_section(&mut text, CONFLICT_MARKER_SEPARATOR, their_chunk);
+            push_line(&mut text, CONFLICT_MARKER_THEIRS);
+        }
+
+        if stable.is_none() {
+            break;
+        }
+
+        i = b;
+        j = o;
+        k = t;
+    }
+
+    MergeResult { text, conflicts }
+}
+
+fn split_lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+fn match_lines(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; base.len()];
+
+    for op in capture_diff_slices(Algorithm::Myers, base, other) {
+        if let DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } = op
+        {
+            for idx in 0..len {
+                matches[old_index + idx] = Some(new_index + idx);
+            }
+        }
+    }
+
+    matches
+}
+
+fn push_conflict_section(text: &mut String, marker: &str, lines: &[&str]) {
+    push_line(text, marker);
+    lines.iter().for_each(|line| text.push_str(line));
+}
+
+fn push_line(text: &mut String, line: &str) {
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(line);
+    text.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_text() {
+        let base = "a\nb\nc\nd\n";
+
+        assert_eq!(
+            merge_text(base, "a\nB\nc\nd\n", "a\nb\nc\nD\n"),
+            MergeResult {
+                text: "a\nB\nc\nD\n".to_owned(),
+                conflicts: 0
+            }
+        );
+
+        assert_eq!(
+            merge_text(base, "a\nb\nc\nd\ne\n", "z\na\nb\nc\nd\n"),
+            MergeResult {
+                text: "z\na\nb\nc\nd\ne\n".to_owned(),
+                conflicts: 0
+            }
+        );
+
+        assert_eq!(
+            merge_text(base, "a\nX\nc\nd\n", "a\nY\nc\nd\n"),
+            MergeResult {
+                text: "a\n<<<<<<< project\nX\n=======\nY\n>>>>>>> template\nc\nd\n".to_owned(),
+                conflicts: 1
+            }
+        );
+
+        assert_eq!(
+            merge_text("a", "b", "c"),
+            MergeResult {
+                text: "<<<<<<< project\nb\n=======\nc\n>>>>>>> template\n".to_owned(),
+                conflicts: 1
+            }
+        );
+    }
+}
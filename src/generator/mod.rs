@@ -1,23 +1,71 @@
+pub use self::cache::{
+    clear_cached_templates, list_cached_templates, update_cached_templates, CachePolicy, ResponseCache,
+};
+pub use self::info::{FileRule, TemplateInfo};
+pub use self::options::GenerateOptions;
+pub use self::update::update_project_from_template;
+use crate::config::UserConfig;
+use crate::generator::progress::Progress;
 use crate::generator::project_template::ProjectTemplate;
 use crate::prelude::*;
-use std::fs;
+use crate::types::OutputFormat;
+use crate::utils;
 use std::path::Path;
 
+mod archive;
+mod cache;
+mod conflict;
+mod credentials;
+mod git;
+mod info;
+mod merge;
+mod options;
+mod plan;
 mod post_generator;
+mod progress;
 mod project_template;
+mod transaction;
+mod update;
 
-pub fn gen_source_code_from_template<P: AsRef<Path>, T: AsRef<str>>(project_dir: P, template: T) -> crate::Result<()> {
-    if let Err(err) = fs::create_dir_all(project_dir.as_ref()) {
-        return Err(crate::Error::new(format!(
-            "Couldn't create the project directory: {}",
-            err
-        )));
+pub fn gen_source_code_from_template<P: AsRef<Path>, T: AsRef<str>>(
+    project_dir: P,
+    template: T,
+    options: &GenerateOptions,
+) -> crate::Result<()> {
+    // The project dir is created only when the generated files are moved into place.
+    let project_dir = utils::absolute_path(project_dir.as_ref()).context("Couldn't get the absolute project path")?;
+
+    let template = resolve_template(template.as_ref(), options, Progress::Stdout)?;
+
+    template.disburse(project_dir, options)
+}
+
+/// Prints the metadata, the data variables, the conditional files and the post generate commands of a template
+/// without generating a project.
+pub fn show_template_info<T: AsRef<str>>(
+    template: T,
+    options: &GenerateOptions,
+    format: OutputFormat,
+) -> crate::Result<()> {
+    // The progress goes to stderr to keep the stdout to the template info.
+    let template = resolve_template(template.as_ref(), options, Progress::Stderr)?;
+
+    info::show_template_info(template, options.offline, format)
+}
+
+fn resolve_template(template: &str, options: &GenerateOptions, progress: Progress) -> crate::Result<ProjectTemplate> {
+    let user_config = UserConfig::load()?;
+
+    let mut template = match user_config.alias(template) {
+        Some(spec) => {
+            progress.println(format!("Using the template alias {}: {}", template, spec));
+            ProjectTemplate::parse(spec)
+        }
+        None => ProjectTemplate::parse(template),
+    };
+    if options.template_ref.is_some() {
+        template.git_ref = options.template_ref.clone();
     }
 
-    ProjectTemplate::parse(template).disburse(
-        project_dir
-            .as_ref()
-            .canonicalize()
-            .context("Couldn't get the absolute project path")?,
-    )
+    Ok(template)
 }
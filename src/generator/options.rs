@@ -0,0 +1,25 @@
+use crate::types::ConflictStrategy;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone)]
+pub struct GenerateOptions {
+    /// The git branch, tag or commit of the template given through `--ref`, overrides the one in the specifier.
+    pub template_ref: Option<String>,
+    /// The template data values provided upfront e.g. through `--data` or `--set`.
+    pub data: HashMap<String, Value>,
+    /// The default answers from the user config, they replace the template's default values.
+    pub default_data: HashMap<String, Value>,
+    /// Use the default values for the optional template data instead of prompting.
+    pub use_defaults: bool,
+    /// Only print the generation plan without touching the project directory.
+    pub dry_run: bool,
+    /// What to do with the existing project files, it takes precedence over the template config's per-path defaults.
+    pub on_conflict: Option<ConflictStrategy>,
+    /// Only render the template files, skips recording the answers, git init and the post generate steps.
+    pub render_only: bool,
+    /// Don't initialize a git repo in the generated project.
+    pub skip_git_init: bool,
+    /// Use only the cached templates without any network access.
+    pub offline: bool,
+}
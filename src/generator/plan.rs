@@ -0,0 +1,278 @@
+use crate::constants;
+use crate::types::ConflictStrategy;
+use colored::*;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PlanAction {
+    Copy,
+    Render,
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlanEntry {
+    pub action: PlanAction,
+    pub source_full_path: PathBuf,
+    pub source_rel_path: PathBuf,
+    pub target_rel_path: Option<PathBuf>,
+    pub target_exists: bool,
+    pub conflict_strategy: ConflictStrategy,
+}
+
+#[derive(Debug, Default)]
+pub struct GenerationPlan {
+    pub entries: Vec<PlanEntry>,
+    pub post_generate_commands: Vec<String>,
+}
+
+#[derive(Default)]
+struct PlanTreeNode<'a> {
+    children: BTreeMap<String, PlanTreeNode<'a>>,
+    entries: Vec<&'a PlanEntry>,
+}
+
+impl GenerationPlan {
+    pub fn files(&self) -> impl Iterator<Item = &PlanEntry> {
+        self.entries.iter().filter(|entry| entry.action != PlanAction::Skip)
+    }
+
+    pub fn collisions(&self) -> Vec<(&Path, Vec<&PlanEntry>)> {
+        let mut targets: BTreeMap<&Path, Vec<&PlanEntry>> = BTreeMap::new();
+
+        for entry in self.files() {
+            if let Some(ref target) = entry.target_rel_path {
+                targets.entry(target.as_path()).or_default().push(entry);
+            }
+        }
+
+        targets.into_iter().filter(|(_, entries)| entries.len() > 1).collect()
+    }
+
+    pub fn ensure_no_collisions(&self) -> crate::Result<()> {
+        let collisions = self.collisions();
+        if collisions.is_empty() {
+            return Ok(());
+        }
+
+        let details = collisions
+            .iter()
+            .map(|(target, entries)| {
+                format!(
+                    "    {} <- {}",
+                    target.to_str().unwrap_or(""),
+                    entries
+                        .iter()
+                        .map(|entry| entry.source_rel_path.to_str().unwrap_or(""))
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        Err(crate::Error::new(format!(
+            "Multiple template files map to the same project file:\n{}",
+            details
+        )))
+    }
+
+    pub fn ensure_no_failing_conflicts(&self) -> crate::Result<()> {
+        let failing = self
+            .files()
+            .filter(|entry| entry.target_exists && entry.conflict_strategy == ConflictStrategy::Fail)
+            .filter_map(|entry| entry.target_rel_path.as_ref())
+            .map(|target| format!("    {}", target.to_str().unwrap_or("")))
+            .collect::<Vec<String>>();
+
+        if failing.is_empty() {
+            return Ok(());
+        }
+
+        Err(crate::Error::new(format!(
+            "The following project files already exist:\n{}",
+            failing.join("\n")
+        )))
+    }
+
+    pub fn print(&self, project_dir: &Path) {
+        let collided_targets = self
+            .collisions()
+            .into_iter()
+            .map(|(target, _)| target.to_path_buf())
+            .collect::<HashSet<PathBuf>>();
+
+        let mut root = PlanTreeNode::default();
+        for entry in self.files() {
+            if let Some(ref target) = entry.target_rel_path {
+                let mut node = &mut root;
+                for component in target.iter() {
+                    node = node
+                        .children
+                        .entry(component.to_str().unwrap_or("").to_owned())
+                        .or_default();
+                }
+                node.entries.push(entry);
+            }
+        }
+
+        println!(
+            "\nDry run: the following files would be generated in {}\n",
+            project_dir.to_str().unwrap_or("").green()
+        );
+        println!("{}", project_dir.file_name().and_then(|n| n.to_str()).unwrap_or(""));
+        print_tree_node(&root, "", &collided_targets);
+
+        let skipped = self
+            .entries
+            .iter()
+            .filter(|entry| entry.action == PlanAction::Skip)
+            .collect::<Vec<&PlanEntry>>();
+
+        if !skipped.is_empty() {
+            println!("\nSkipped template files:");
+            for entry in skipped {
+                println!(
+                    "    {} {}",
+                    "skip".bright_black(),
+                    entry.source_rel_path.to_str().unwrap_or("")
+                );
+            }
+        }
+
+        if !collided_targets.is_empty() {
+            println!(
+                "\n{} {} project file(s) are generated from multiple template files, marked as {} above",
+                "Warning:".yellow(),
+                collided_targets.len(),
+                "collision".red()
+            );
+        }
+
+        if !self.post_generate_commands.is_empty() {
+            println!("\nPost generate commands:");
+            for command in self.post_generate_commands.iter() {
+                println!("    {} {}", "$".bold(), command.trim().green());
+            }
+        }
+
+        println!();
+    }
+}
+
+impl PlanEntry {
+    fn describe(&self) -> String {
+        let target_name = self
+            .target_rel_path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or("");
+
+        let mut desc = match self.action {
+            PlanAction::Copy => format!("{}", "copy".cyan()),
+            PlanAction::Render => format!(
+                "{} from {}",
+                "render".cyan(),
+                self.source_rel_path.to_str().unwrap_or("")
+            ),
+            PlanAction::Skip => format!("{}", "skip".bright_black()),
+        };
+
+        if self.target_exists {
+            let conflict_desc = match self.conflict_strategy {
+                ConflictStrategy::Backup => format!(
+                    "existing file renamed to {}{}",
+                    target_name,
+                    constants::CONFLICT_BACKUP_FILE_EXTENSION
+                ),
+                ConflictStrategy::Overwrite => "existing file overwritten".to_owned(),
+                ConflictStrategy::Skip => "existing file kept".to_owned(),
+                ConflictStrategy::Prompt => "asks before replacing the existing file".to_owned(),
+                ConflictStrategy::Fail => format!("{}", "fails as the file exists".red()),
+            };
+
+            desc.push_str(format!(", {}", conflict_desc).as_str());
+        }
+
+        desc
+    }
+}
+
+fn print_tree_node(node: &PlanTreeNode, prefix: &str, collided_targets: &HashSet<PathBuf>) {
+    let count = node.children.len();
+
+    for (idx, (name, child)) in node.children.iter().enumerate() {
+        let is_last = idx + 1 == count;
+        let branch = if is_last { "└── " } else { "├── " };
+
+        if child.entries.is_empty() {
+            println!("{}{}{}/", prefix, branch, name);
+        } else {
+            let collided = child
+                .entries
+                .first()
+                .and_then(|entry| entry.target_rel_path.as_ref())
+                .map(|target| collided_targets.contains(target))
+                .unwrap_or(false);
+
+            let desc = child
+                .entries
+                .iter()
+                .map(|entry| entry.describe())
+                .collect::<Vec<String>>()
+                .join(" | ");
+
+            if collided {
+                println!("{}{}{} [{}] {}", prefix, branch, name, desc, "collision".red());
+            } else {
+                println!("{}{}{} [{}]", prefix, branch, name, desc);
+            }
+        }
+
+        let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+        print_tree_node(child, child_prefix.as_str(), collided_targets);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: PlanAction, source: &str, target: Option<&str>) -> PlanEntry {
+        PlanEntry {
+            action,
+            source_full_path: PathBuf::from("/template").join(source),
+            source_rel_path: PathBuf::from(source),
+            target_rel_path: target.map(PathBuf::from),
+            target_exists: false,
+            conflict_strategy: ConflictStrategy::Backup,
+        }
+    }
+
+    #[test]
+    fn test_generation_plan_collisions() {
+        let plan = GenerationPlan {
+            entries: vec![
+                entry(PlanAction::Copy, "README.md", Some("README.md")),
+                entry(PlanAction::Render, "README.tpl.boiler", Some("README.md")),
+                entry(PlanAction::Render, "src/main.rs.boiler", Some("src/main.rs")),
+                entry(PlanAction::Skip, "src/lib.rs", None),
+            ],
+            post_generate_commands: vec![],
+        };
+
+        let collisions = plan.collisions();
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].0, Path::new("README.md"));
+        assert_eq!(collisions[0].1.len(), 2);
+        assert!(plan.ensure_no_collisions().is_err());
+
+        let plan = GenerationPlan {
+            entries: plan.entries[1..].to_vec(),
+            post_generate_commands: vec![],
+        };
+        assert!(plan.ensure_no_collisions().is_ok());
+    }
+}
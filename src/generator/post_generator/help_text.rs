@@ -27,7 +27,7 @@ pub fn handle_post_generate_help_text(
         )?,
         Value::Object(ref texts_map) => {
             for (key, val) in texts_map.iter() {
-                if let Some(_) = key.split("+").find(|p| p.trim() == OS) {
+                if key.split("+").find(|p| p.trim() == OS).is_some() {
                     print_help_texts(
                         val,
                         template_dir,
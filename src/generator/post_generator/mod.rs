@@ -4,7 +4,9 @@ use std::env;
 use std::path::Path;
 
 pub use self::help_text::handle_post_generate_help_text;
-pub use self::post_command::handle_post_generate_command;
+pub use self::post_command::{
+    group_post_generate_commands, handle_post_generate_command, resolve_post_generate_commands,
+};
 pub use self::var_subs::substitute_variable_in_text;
 
 mod help_text;
@@ -24,12 +26,12 @@ pub fn gen_extra_template_data(
             .file_name()
             .and_then(|f| f.to_str())
             .map(|f| f.to_owned())
-            .unwrap_or(String::new()),
+            .unwrap_or_default(),
     );
 
     map.insert(
         constants::TEMPLATE_EXTRA_VAR_PROJECT_DIR_FULL_PATH.to_owned(),
-        project_dir.to_str().map(|p| p.to_owned()).unwrap_or(String::new()),
+        project_dir.to_str().map(|p| p.to_owned()).unwrap_or_default(),
     );
 
     map.insert(
@@ -44,15 +46,12 @@ pub fn gen_extra_template_data(
 
     map.insert(
         constants::TEMPLATE_EXTRA_VAR_TEMPLATE_PATH.to_owned(),
-        template_dir.to_str().map(|p| p.to_owned()).unwrap_or(String::new()),
+        template_dir.to_str().map(|p| p.to_owned()).unwrap_or_default(),
     );
 
     map.insert(
         constants::TEMPLATE_EXTRA_VAR_TEMPLATE_SOURCE_PATH.to_owned(),
-        template_source_dir
-            .to_str()
-            .map(|p| p.to_owned())
-            .unwrap_or(String::new()),
+        template_source_dir.to_str().map(|p| p.to_owned()).unwrap_or_default(),
     );
 
     map
@@ -4,7 +4,7 @@ use crate::prelude::*;
 use crate::utils::json_val_to_actual_str;
 use colored::*;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env::consts::OS;
 use std::path::Path;
 use std::process::{Command, Stdio};
@@ -16,52 +16,72 @@ pub fn handle_post_generate_command(
     project_dir: &Path,
     template_data: &HashMap<&str, Value>,
 ) -> crate::Result<()> {
+    for command in resolve_post_generate_commands(val) {
+        execute_single_command(
+            command.as_str(),
+            template_dir,
+            template_source_dir,
+            project_dir,
+            template_data,
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn resolve_post_generate_commands(val: &Value) -> Vec<String> {
+    let mut commands = Vec::new();
+
     match val {
-        Value::String(_) | Value::Array(_) => {
-            execute_commands(val, template_dir, template_source_dir, project_dir, template_data)?
-        }
+        Value::String(_) | Value::Array(_) => collect_commands(val, &mut commands),
         Value::Object(ref commands_map) => {
             for (key, val) in commands_map.iter() {
-                if let Some(_) = key.split("+").find(|p| p.trim() == OS) {
-                    execute_commands(val, template_dir, template_source_dir, project_dir, template_data)?;
+                if key.split('+').any(|p| p.trim() == OS) {
+                    collect_commands(val, &mut commands);
                     break;
                 }
             }
 
             if let Some(val) = commands_map.get(constants::TEMPLATE_OS_FLAG_ALL) {
-                execute_commands(val, template_dir, template_source_dir, project_dir, template_data)?;
+                collect_commands(val, &mut commands);
             }
         }
         _ => (),
     }
 
-    Ok(())
+    commands
 }
 
-fn execute_commands(
-    val: &Value,
-    template_dir: &Path,
-    template_source_dir: &Path,
-    project_dir: &Path,
-    template_data: &HashMap<&str, Value>,
-) -> crate::Result<()> {
+/// Groups all the post generate commands by the OS key, the commands given as a string or an array apply to all
+/// the OSs.
+pub fn group_post_generate_commands(val: &Value) -> BTreeMap<String, Vec<String>> {
+    let mut groups = BTreeMap::new();
+
     match val {
-        Value::String(ref command) => execute_single_command(
-            command.as_str(),
-            template_dir,
-            template_source_dir,
-            project_dir,
-            template_data,
-        )?,
-        Value::Array(ref commands) => {
-            for command in commands.iter().filter_map(|v| v.as_str()) {
-                execute_single_command(command, template_dir, template_source_dir, project_dir, template_data)?
+        Value::String(_) | Value::Array(_) => {
+            let mut commands = Vec::new();
+            collect_commands(val, &mut commands);
+            groups.insert(constants::TEMPLATE_OS_FLAG_ALL.to_owned(), commands);
+        }
+        Value::Object(ref commands_map) => {
+            for (key, val) in commands_map.iter() {
+                let os_key = key.split('+').map(|p| p.trim()).collect::<Vec<&str>>().join(" + ");
+                collect_commands(val, groups.entry(os_key).or_insert_with(Vec::new));
             }
         }
         _ => (),
     }
 
-    Ok(())
+    groups.retain(|_, commands| !commands.is_empty());
+    groups
+}
+
+fn collect_commands(val: &Value, commands: &mut Vec<String>) {
+    match val {
+        Value::String(ref command) => commands.push(command.to_owned()),
+        Value::Array(ref values) => commands.extend(values.iter().filter_map(|v| v.as_str()).map(|v| v.to_owned())),
+        _ => (),
+    }
 }
 
 fn execute_single_command(
@@ -86,7 +106,7 @@ fn execute_single_command(
 
     println!("{} {}", "$".bold(), command.green());
     Command::new(program)
-        .args(&args)
+        .args(args)
         .stdin(Stdio::inherit())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
@@ -24,10 +24,10 @@ pub fn substitute_variable_in_text(
 
             template_data
                 .get(var_name)
-                .map(|v| utils::json_val_to_actual_str(v))
+                .map(utils::json_val_to_actual_str)
                 .or_else(|| extra_data.get(&var_name.to_owned()).cloned())
                 .or_else(|| env::var(var_name).ok())
-                .unwrap_or(String::new())
+                .unwrap_or_default()
         })
         .to_string()
 }
@@ -0,0 +1,18 @@
+use std::fmt::Display;
+
+/// Where the template fetch progress is written. The generation prints it with the rest of its output, while the
+/// `info` subcommand writes it to stderr to keep its stdout to the template info e.g. the JSON.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Progress {
+    Stdout,
+    Stderr,
+}
+
+impl Progress {
+    pub fn println<T: Display>(self, msg: T) {
+        match self {
+            Progress::Stdout => println!("{}", msg),
+            Progress::Stderr => eprintln!("{}", msg),
+        }
+    }
+}
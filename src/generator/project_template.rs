@@ -1,153 +1,638 @@
+use crate::config::UserConfig;
 use crate::constants;
 use crate::data_prompts;
+use crate::generator::archive;
+use crate::generator::cache::{CacheEntry, CacheEntryKind, CachePolicy, TemplateCache};
+use crate::generator::conflict;
+use crate::generator::git;
+use crate::generator::options::GenerateOptions;
+use crate::generator::plan::{GenerationPlan, PlanAction, PlanEntry};
 use crate::generator::post_generator::{
-    gen_extra_template_data, handle_post_generate_command, handle_post_generate_help_text, substitute_variable_in_text,
+    gen_extra_template_data, handle_post_generate_command, handle_post_generate_help_text,
+    resolve_post_generate_commands, substitute_variable_in_text,
 };
+use crate::generator::progress::Progress;
+use crate::generator::transaction::Transaction;
 use crate::prelude::*;
+use crate::registry::RegistryIndex;
 use crate::template_engine::TemplateEngine;
-use crate::types::{CondFileMap, ConfigFileType, TemplateConfig, TemplateData, TemplateDataType};
-use crate::utils;
+use crate::types::{
+    AnswersFile, CondFileMap, ConfigFileType, ConflictStrategy, TemplateConfig, TemplateData, TemplateDataType,
+    TemplateProvenance,
+};
+use crate::utils::{self, TempDir};
 use colored::*;
+use semver::{Version, VersionReq};
 use serde_json::Value;
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::env;
+use std::fmt::{self, Display, Formatter};
 use std::fs;
 use std::fs::FileType;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use url::Url;
 
 #[derive(Debug, PartialEq)]
-pub enum ProjectTemplate {
+pub struct ProjectTemplate {
+    pub source: TemplateSource,
+    /// The git branch, tag or commit to generate from, the remote's default branch is used if it's not given.
+    pub git_ref: Option<String>,
+    /// A semver range e.g. `^2.0` to generate from the highest matching git tag, the git ref takes precedence.
+    pub version_req: Option<String>,
+    /// The template dir inside the repo or the local path e.g. `templates/web` in `owner/repo/templates/web`.
+    pub subdir: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TemplateSource {
     InBuilt(String),
     Github(String, String),
+    /// A repo on a forge given by its prefix and repo path e.g. `gitlab:group/subgroup/repo`.
+    Forge(String, String),
     Local(String),
     AnyGitRepo(String),
+    /// A `.tar.gz`, `.tgz` or `.zip` archive given as a local path.
+    LocalArchive(String),
+    /// A `.tar.gz`, `.tgz` or `.zip` archive given as an HTTP URL.
+    RemoteArchive(String),
+    /// An SSH, scp-style or git protocol URL e.g. `git@github.com:org/repo.git`, passed to git unchanged.
+    GitUrl(String),
+}
+
+/// A fetched template dir, the temporary dirs are removed when it's dropped.
+#[derive(Debug)]
+pub enum TemplateDir {
+    Local(PathBuf),
+    /// A dir in the template cache, it's kept for the next runs.
+    Cached(PathBuf),
+    /// The temporary dir is held only to be removed on drop, the path can be a subdirectory of it.
+    Temp {
+        _temp_dir: TempDir,
+        path: PathBuf,
+    },
+}
+
+impl TemplateDir {
+    pub fn path(&self) -> &Path {
+        match self {
+            TemplateDir::Local(ref path) | TemplateDir::Cached(ref path) => path.as_path(),
+            TemplateDir::Temp { ref path, .. } => path.as_path(),
+        }
+    }
 }
 
 impl ProjectTemplate {
     pub fn parse<T: AsRef<str>>(template: T) -> ProjectTemplate {
         let template = template.as_ref().trim();
 
-        if template.starts_with(constants::TEMPLATE_PREFIX_FILE) {
-            ProjectTemplate::Local((&template[constants::TEMPLATE_PREFIX_FILE.len()..]).to_owned())
+        let (template, git_ref) = match template.split_once(constants::TEMPLATE_REF_SEPARATOR) {
+            Some((template, git_ref)) => (template, Some(git_ref.trim()).filter(|r| !r.is_empty())),
+            None => (template, None),
+        };
+
+        let mut version_req = None;
+        let mut subdir = None;
+        let source = if let Some(path) = template.strip_prefix(constants::TEMPLATE_PREFIX_FILE) {
+            let (path, path_subdir) = split_subdir(path, 1);
+            subdir = path_subdir;
+
+            if archive::is_archive(path) {
+                TemplateSource::LocalArchive(path.to_owned())
+            } else {
+                TemplateSource::Local(path.to_owned())
+            }
         } else if template.starts_with(constants::TEMPLATE_PREFIX_HTTP)
             || template.starts_with(constants::TEMPLATE_PREFIX_HTTPS)
         {
-            ProjectTemplate::AnyGitRepo(template.to_owned())
-        } else if template.contains("/") {
-            let mut parts = template.split("/");
-            ProjectTemplate::Github(parts.next().unwrap().to_owned(), parts.next().unwrap().to_owned())
+            let (url, url_subdir) = split_subdir(template, template.find("://").map(|idx| idx + 3).unwrap_or(0));
+            subdir = url_subdir;
+
+            if archive::is_archive(url) {
+                TemplateSource::RemoteArchive(url.to_owned())
+            } else {
+                TemplateSource::AnyGitRepo(url.to_owned())
+            }
+        } else if constants::TEMPLATE_GIT_URL_PREFIXES
+            .iter()
+            .any(|prefix| template.starts_with(prefix))
+        {
+            let (url, url_subdir) = split_subdir(template, template.find("://").map(|idx| idx + 3).unwrap_or(0));
+            subdir = url_subdir;
+            TemplateSource::GitUrl(url.to_owned())
+        } else if is_scp_like_git_url(template) {
+            let (url, url_subdir) = split_subdir(template, template.find(':').map(|idx| idx + 1).unwrap_or(0));
+            subdir = url_subdir;
+            TemplateSource::GitUrl(url.to_owned())
+        } else if let Some((prefix, path)) = split_forge_prefix(template) {
+            let (path, req) = match path.split_once(constants::TEMPLATE_VERSION_SEPARATOR) {
+                Some((path, req)) => (path, Some(req.trim()).filter(|r| !r.is_empty())),
+                None => (path, None),
+            };
+            version_req = req;
+
+            let (path, path_subdir) = split_subdir(path, 0);
+            subdir = path_subdir;
+            TemplateSource::Forge(prefix.to_owned(), path.trim_matches('/').to_owned())
         } else {
-            ProjectTemplate::InBuilt(template.to_owned())
+            let (template, req) = match template.split_once(constants::TEMPLATE_VERSION_SEPARATOR) {
+                Some((template, req)) => (template, Some(req.trim()).filter(|r| !r.is_empty())),
+                None => (template, None),
+            };
+            version_req = req;
+
+            if template.contains('/') {
+                let mut parts = template.splitn(3, '/');
+                let handle = parts.next().unwrap().to_owned();
+                let repo = parts.next().unwrap().to_owned();
+                subdir = parts
+                    .next()
+                    .map(|path| path.trim_matches('/'))
+                    .filter(|path| !path.is_empty());
+                TemplateSource::Github(handle, repo)
+            } else {
+                TemplateSource::InBuilt(template.to_owned())
+            }
+        };
+
+        ProjectTemplate {
+            source,
+            git_ref: git_ref.map(|r| r.to_owned()),
+            version_req: version_req.map(|r| r.to_owned()),
+            subdir: subdir.map(|path| path.to_owned()),
         }
     }
 
-    pub fn disburse<P: AsRef<Path>>(&self, project_dir: P) -> crate::Result<()> {
-        match self {
-            ProjectTemplate::InBuilt(ref name) => self.disburse_in_built_template(name.as_str(), project_dir),
-            ProjectTemplate::Github(ref handle, ref repo) => {
-                self.disburse_github_template(handle.as_str(), repo.as_str(), project_dir)
+    pub fn disburse<P: AsRef<Path>>(&self, project_dir: P, options: &GenerateOptions) -> crate::Result<()> {
+        let template_dir = self.fetch(None, options.offline, Progress::Stdout)?;
+
+        self.gen_source_code(template_dir.path(), project_dir.as_ref(), options)
+            .map(|_| ())
+    }
+
+    /// Fetches the template at the given commit, or at its git ref if the commit is not given. The remote
+    /// templates are kept in the template cache, in offline mode only the cached templates are used.
+    pub fn fetch(&self, commit: Option<&str>, offline: bool, progress: Progress) -> crate::Result<TemplateDir> {
+        let git_ref = commit.or(self.git_ref.as_deref());
+        let user_config = UserConfig::load()?;
+
+        let (repo_url, display_name) = match self.source {
+            TemplateSource::LocalArchive(_) | TemplateSource::RemoteArchive(_) => {
+                if git_ref.is_some() {
+                    return Err(crate::Error::new("A git ref can't be used with an archive template"));
+                }
+                return self.fetch_archive(&user_config, offline, progress);
+            }
+            TemplateSource::InBuilt(ref name) => {
+                if let Some(template) = self.resolve_from_registries(&user_config, name, offline, progress)? {
+                    return template.fetch(commit, offline, progress);
+                }
+
+                let repo_url = self.resolve_in_built_repo(&user_config, name, offline, progress)?;
+                (repo_url, name.to_owned())
+            }
+            TemplateSource::Github(ref handle, ref repo) => {
+                let repo_url = Url::parse(constants::TEMPLATE_GITHUB_PREFIX)
+                    .unwrap()
+                    .join(format!("{}/", handle).as_str())
+                    .and_then(|url| url.join(repo))
+                    .context("Failed to create Github template repo URL")?;
+                (repo_url.to_string(), format!("{}/{}", handle, repo))
+            }
+            TemplateSource::Forge(ref prefix, ref path) => {
+                let base_url = user_config.forge_base_url(prefix).ok_or_else(|| {
+                    crate::Error::new(format!(
+                        "Unknown template forge prefix: {}, declare it under 'forges' in the user config file",
+                        prefix
+                    ))
+                })?;
+
+                let base_url = utils::or(base_url.ends_with('/'), base_url.clone(), format!("{}/", base_url));
+                let repo_url = Url::parse(base_url.as_str())
+                    .and_then(|url| url.join(path))
+                    .context(format!(
+                        "Failed to create the template repo URL for the forge: {}",
+                        prefix
+                    ))?;
+                (repo_url.to_string(), format!("{}:{}", prefix, path))
+            }
+            TemplateSource::Local(ref path) => {
+                let template_path = Path::new(path)
+                    .canonicalize()
+                    .context("Provided local template doesn't exist")?;
+
+                if git_ref.is_none() {
+                    return self.template_subdir(template_path.as_path()).map(TemplateDir::Local);
+                }
+
+                let repo_url = Url::from_file_path(template_path.as_path()).map_err(|_| {
+                    crate::Error::new(format!(
+                        "Provided local template path is invalid: {}",
+                        template_path.to_str().unwrap_or("")
+                    ))
+                })?;
+                (
+                    repo_url.to_string(),
+                    format!("{}{}", constants::TEMPLATE_PREFIX_FILE, path),
+                )
+            }
+            TemplateSource::AnyGitRepo(ref url) => {
+                let repo_url = Url::parse(url).context("Provided template Git repo URL is invalid")?;
+                (repo_url.to_string(), url.to_owned())
             }
-            ProjectTemplate::Local(ref path) => self.disburse_local_template(path.as_str(), project_dir),
-            ProjectTemplate::AnyGitRepo(ref url) => self.disburse_any_git_repo_template(url.as_str(), project_dir),
+            TemplateSource::GitUrl(ref url) => (url.to_owned(), url.to_owned()),
+        };
+
+        // The URL rewrite rules e.g. to a mirror apply to the remote repos only.
+        let repo_url = match self.source {
+            TemplateSource::Local(_) => repo_url,
+            _ => user_config.rewrite_url(repo_url.as_str()),
+        };
+
+        // The repo URL can have the credentials in it.
+        let display_name = match self.subdir {
+            Some(ref subdir) => format!("{}{}{}", display_name, self.source.subdir_separator(), subdir),
+            None => display_name,
+        };
+        let display_name = utils::redact_credentials(display_name.as_str());
+
+        let cache = TemplateCache::open();
+
+        let resolved_tag;
+        let git_ref = match (git_ref, self.version_req.as_ref()) {
+            (None, Some(version_req)) => {
+                resolved_tag = self.resolve_version_tag(&cache, &repo_url, version_req.as_str(), offline, progress)?;
+                Some(resolved_tag.as_str())
+            }
+            (git_ref, _) => git_ref,
+        };
+
+        let subdir = self.subdir.as_deref();
+        let entry = match cache.get(repo_url.as_str(), git_ref, subdir) {
+            Some(entry) => self.refresh_cached(entry, display_name.as_str(), offline, progress),
+            None if offline => {
+                return Err(crate::Error::new(format!(
+                    "The template is not in the cache: {}, run without --offline to fetch it",
+                    display_name
+                )))
+            }
+            None => {
+                match git_ref {
+                    Some(git_ref) => progress.println(format!(
+                        "Cloning template: {} at {}",
+                        display_name.as_str().green(),
+                        git_ref.green()
+                    )),
+                    None => progress.println(format!("Cloning template: {}", display_name.as_str().green())),
+                };
+
+                let entry = CacheEntry::new(display_name, repo_url.clone(), CacheEntryKind::Git, git_ref, subdir);
+                // A clone without the template subdirectory is not kept in the cache.
+                let entry = cache.insert(entry, |dest_dir| {
+                    git::clone(repo_url.as_str(), dest_dir, git_ref, subdir).wrap()?;
+                    self.template_subdir(dest_dir).map(|_| ())
+                });
+
+                progress.println("");
+                entry?
+            }
+        };
+
+        let repo_dir = entry.template_root()?;
+        let template_dir = self.template_subdir(repo_dir.as_path())?;
+
+        // The cached submodules and LFS files are used as they are in offline mode.
+        if !offline {
+            self.fetch_submodules_and_lfs(repo_dir.as_path(), template_dir.as_path(), progress)?;
         }
+
+        Ok(TemplateDir::Cached(template_dir))
     }
 
-    fn disburse_in_built_template<P: AsRef<Path>>(&self, name: &str, project_dir: P) -> crate::Result<()> {
-        let repo_url = Url::parse(constants::TEMPLATE_IN_BUILT_PREFIX)
-            .unwrap()
-            .join(name)
-            .context("Failed to create in-built template repo URL")?;
+    /// Finds a bare template name in the configured registries, the first registry having it wins. The template is
+    /// fetched at the latest version of the registry entry unless a ref or a version is given.
+    fn resolve_from_registries(
+        &self,
+        user_config: &UserConfig,
+        name: &str,
+        offline: bool,
+        progress: Progress,
+    ) -> crate::Result<Option<ProjectTemplate>> {
+        let cache_policy = CachePolicy {
+            ttl: user_config.search_cache_ttl(),
+            refresh: false,
+            offline,
+        };
 
-        let template_path = self.clone_repo(&repo_url, format!("Cloning template: {}", name.green()).as_str())?;
+        for location in user_config.registries.iter() {
+            let location = user_config.rewrite_url(location);
 
-        self.gen_source_code(template_path.as_path(), project_dir.as_ref())?;
+            let index = match RegistryIndex::load(location.as_str(), Some(&cache_policy)) {
+                Ok(index) => index,
+                Err(err) => {
+                    progress.println(format!("{} {}", "Warning:".yellow(), err));
+                    continue;
+                }
+            };
+
+            let entry = match index.find(name) {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            let mut template = ProjectTemplate::parse(entry.source.as_str());
+            if let TemplateSource::InBuilt(_) = template.source {
+                return Err(crate::Error::new(format!(
+                    "The registry source of the template {} can't be a bare name: {}",
+                    name, entry.source
+                )));
+            }
 
-        fs::remove_dir_all(template_path.as_path()).wrap()?;
+            if self.git_ref.is_some() || self.version_req.is_some() {
+                template.git_ref = self.git_ref.clone();
+                template.version_req = self.version_req.clone();
+            } else if template.git_ref.is_none() && template.version_req.is_none() {
+                template.version_req = entry
+                    .latest_version
+                    .as_ref()
+                    .map(|version| format!("={}", version.trim_start_matches('v')));
+            }
 
-        Ok(())
+            if self.subdir.is_some() {
+                template.subdir = self.subdir.clone();
+            }
+
+            progress.println(format!(
+                "Resolved template {} to {} from the registry {}",
+                name,
+                utils::redact_credentials(template.to_string().as_str()).green(),
+                utils::redact_credentials(location.as_str())
+            ));
+
+            return Ok(Some(template));
+        }
+
+        Ok(None)
     }
 
-    fn disburse_github_template<P: AsRef<Path>>(&self, handle: &str, repo: &str, project_dir: P) -> crate::Result<()> {
-        let repo_url = Url::parse(constants::TEMPLATE_GITHUB_PREFIX)
-            .unwrap()
-            .join(format!("{}/", handle).as_str())
-            .and_then(|url| url.join(repo))
-            .context("Failed to create Github template repo URL")?;
+    /// Finds the repo of a bare template name in the configured orgs, the first org having the repo wins. In offline
+    /// mode, or when the orgs can't be reached, the first org having the repo in the cache wins.
+    fn resolve_in_built_repo(
+        &self,
+        user_config: &UserConfig,
+        name: &str,
+        offline: bool,
+        progress: Progress,
+    ) -> crate::Result<String> {
+        let orgs = user_config.orgs();
+        let mut candidates = Vec::with_capacity(orgs.len());
+        for org in orgs.iter() {
+            let repo_url = Url::parse(constants::TEMPLATE_GITHUB_PREFIX)
+                .unwrap()
+                .join(format!("{}/", org).as_str())
+                .and_then(|url| url.join(name))
+                .context("Failed to create in-built template repo URL")?;
+            candidates.push((*org, repo_url.to_string()));
+        }
 
-        let template_path = self.clone_repo(
-            &repo_url,
-            format!("Cloning template: {}", format!("{}/{}", handle, repo).as_str().green()).as_str(),
-        )?;
+        if candidates.len() == 1 {
+            let (org, repo_url) = candidates.remove(0);
+            progress.println(format!("Resolved template {} to {}/{}", name, org, name.green()));
+            return Ok(repo_url);
+        }
+
+        let find_cached = || -> crate::Result<Option<(&str, String)>> {
+            let cached_urls = TemplateCache::open()
+                .entries()?
+                .into_iter()
+                .map(|entry| entry.url)
+                .collect::<Vec<String>>();
+
+            Ok(candidates
+                .iter()
+                .find(|(_, repo_url)| {
+                    cached_urls.contains(&utils::redact_credentials(user_config.rewrite_url(repo_url).as_str()))
+                })
+                .cloned())
+        };
+
+        let found = if offline {
+            find_cached()?
+        } else {
+            let mut found = None;
+            for (org, repo_url) in candidates.iter() {
+                match git::remote_exists(user_config.rewrite_url(repo_url).as_str()) {
+                    Ok(true) => {
+                        found = Some((*org, repo_url.clone()));
+                        break;
+                    }
+                    Ok(false) => continue,
+                    Err(err @ git::GitError::AuthFailed { .. }) => {
+                        // The private and the missing repos can't be told apart without the access to them.
+                        progress.println(format!("{} {}, trying the next org", "Warning:".yellow(), err));
+                        continue;
+                    }
+                    Err(err) => {
+                        found = find_cached()?;
+                        if found.is_none() {
+                            return Err(crate::Error::new(err.to_string()));
+                        }
+                        progress.println(format!("{} {}, using the cached template", "Warning:".yellow(), err));
+                        break;
+                    }
+                }
+            }
+            found
+        };
 
-        self.gen_source_code(template_path.as_path(), project_dir.as_ref())?;
+        let (org, repo_url) = found.ok_or_else(|| {
+            crate::Error::new(format!(
+                "The template {} is not found in the orgs: {}",
+                name,
+                orgs.join(", ")
+            ))
+        })?;
 
-        fs::remove_dir_all(template_path.as_path()).wrap()?;
+        progress.println(format!("Resolved template {} to {}/{}", name, org, name.green()));
 
-        Ok(())
+        Ok(repo_url)
     }
 
-    fn disburse_local_template<P: AsRef<Path>>(&self, path: &str, project_dir: P) -> crate::Result<()> {
-        let template_path = Path::new(path)
-            .canonicalize()
-            .context("Provided local template doesn't exist")?;
+    /// Completes a cloned template with its submodules if the template config opts in, and with its LFS files
+    /// if git LFS is installed.
+    fn fetch_submodules_and_lfs(&self, repo_dir: &Path, template_dir: &Path, progress: Progress) -> crate::Result<()> {
+        let subdir = self.subdir.as_deref();
+
+        let submodules = self
+            .extract_template_config(template_dir)
+            .map(|config| config.template.submodules)
+            .unwrap_or(false);
+
+        if submodules {
+            progress.println("Updating template submodules\n");
+            git::update_submodules(repo_dir, subdir).wrap()?;
+        }
 
-        self.gen_source_code(template_path, project_dir)?;
+        if git::uses_lfs(repo_dir, subdir) && !git::lfs_pull(repo_dir, subdir)? {
+            progress.println(format!(
+                "{} The template stores files in git LFS but git LFS is not installed, they are generated as pointer files\n",
+                "Warning:".yellow()
+            ));
+        }
 
         Ok(())
     }
 
-    fn disburse_any_git_repo_template<P: AsRef<Path>>(&self, url: &str, project_dir: P) -> crate::Result<()> {
-        let repo_url = Url::parse(url).context("Provided template Git repo URL is invalid")?;
+    fn fetch_archive(&self, user_config: &UserConfig, offline: bool, progress: Progress) -> crate::Result<TemplateDir> {
+        let (url, display_name) = match self.source {
+            TemplateSource::RemoteArchive(ref url) => (user_config.rewrite_url(url), utils::redact_credentials(url)),
+            TemplateSource::LocalArchive(ref path) => {
+                let archive_path = Path::new(path)
+                    .canonicalize()
+                    .context("Provided local template archive doesn't exist")?;
 
-        let template_path = self.clone_repo(&repo_url, format!("Cloning template: {}", url.green()).as_str())?;
+                let temp_dir = TempDir::new().context("Couldn't create a temporary folder to unpack the template")?;
+                archive::unpack_archive(archive_path.as_path(), temp_dir.path())?;
+                let path = self.template_subdir(archive::archive_root(temp_dir.path())?.as_path())?;
 
-        self.gen_source_code(template_path.as_path(), project_dir)?;
+                return Ok(TemplateDir::Temp {
+                    _temp_dir: temp_dir,
+                    path,
+                });
+            }
+            _ => unreachable!(),
+        };
 
-        fs::remove_dir_all(template_path.as_path()).wrap()?;
+        let url = url.as_str();
+        let cache = TemplateCache::open();
+        let entry = match cache.get(url, None, None) {
+            // Downloading the whole archive again on every use would defeat the cache.
+            Some(entry) if entry.is_fresh(user_config.archive_cache_ttl()) => {
+                progress.println(format!("Using cached template: {}\n", display_name.green()));
+                entry
+            }
+            Some(entry) => self.refresh_cached(entry, display_name.as_str(), offline, progress),
+            None if offline => {
+                return Err(crate::Error::new(format!(
+                    "The template is not in the cache: {}, run without --offline to fetch it",
+                    display_name
+                )))
+            }
+            None => {
+                progress.println(format!("Downloading template: {}\n", display_name.as_str().green()));
+
+                let entry = CacheEntry::new(display_name, url.to_owned(), CacheEntryKind::Archive, None, None);
+                cache.insert(entry, |dest_dir| {
+                    fs::create_dir(dest_dir).context("Couldn't create a folder to unpack the template")?;
+                    archive::download_and_unpack_archive(url, dest_dir)
+                })?
+            }
+        };
 
-        Ok(())
+        self.template_subdir(entry.template_root()?.as_path())
+            .map(TemplateDir::Cached)
     }
 
-    fn clone_repo(&self, repo_url: &Url, msg: &str) -> crate::Result<PathBuf> {
-        let clone_dir = env::temp_dir().join(utils::gen_uuid());
+    /// Brings a cached template up to date, the cached version is used as is if it can't be updated.
+    fn refresh_cached(
+        &self,
+        mut entry: CacheEntry,
+        display_name: &str,
+        offline: bool,
+        progress: Progress,
+    ) -> CacheEntry {
+        if offline || entry.is_pinned() {
+            progress.println(format!("Using cached template: {}\n", display_name.green()));
+            return entry;
+        }
 
-        fs::create_dir_all(clone_dir.as_path()).context(format!(
-            "Couldn't create a temporary folder to clone a repo: {}",
-            clone_dir.to_str().unwrap_or("")
-        ))?;
+        progress.println(format!("Updating template: {}", display_name.green()));
 
-        println!("{}", msg);
+        if let Err(err) = entry.refresh() {
+            progress.println(format!(
+                "{} Couldn't update the template, using the cached version: {}",
+                "Warning:".yellow(),
+                err
+            ));
+        }
 
-        // The command: git clone <url> --branch <branch> --single-branch [<folder>]
-        let output = Command::new("git")
-            .arg("clone")
-            .arg(repo_url.as_str())
-            .args(&["--branch", constants::TEMPLATE_GIT_REPO_BRANCH])
-            .arg("--single-branch")
-            .arg(clone_dir.as_os_str())
-            .output()
-            .context("The 'git' command not found")?;
+        progress.println("");
+        entry
+    }
 
-        println!();
+    fn template_subdir(&self, repo_dir: &Path) -> crate::Result<PathBuf> {
+        let subdir = match self.subdir {
+            Some(ref subdir) => subdir,
+            None => return Ok(repo_dir.to_path_buf()),
+        };
 
-        if !output.status.success() {
-            return Err(crate::Error::new(format!(
-                "The provided template not found: {}",
-                String::from_utf8(output.stderr).unwrap_or(String::new())
-            )));
+        repo_dir
+            .join(subdir)
+            .canonicalize()
+            .ok()
+            .filter(|path| path.starts_with(repo_dir) && path.is_dir())
+            .ok_or_else(|| crate::Error::new(format!("The template subdirectory doesn't exist: {}", subdir)))
+    }
+
+    /// Finds the highest git tag in the remote repo matching the semver range, in offline mode only the
+    /// cached tags are considered.
+    fn resolve_version_tag(
+        &self,
+        cache: &TemplateCache,
+        repo_url: &str,
+        version_req: &str,
+        offline: bool,
+        progress: Progress,
+    ) -> crate::Result<String> {
+        let req = VersionReq::parse(version_req).context(format!("Invalid template version range: {}", version_req))?;
+
+        if offline {
+            return cache
+                .entries()?
+                .into_iter()
+                .filter(|entry| entry.url == utils::redact_credentials(repo_url) && entry.subdir == self.subdir)
+                .filter_map(|entry| entry.git_ref)
+                .filter_map(|tag| {
+                    Version::parse(tag.trim_start_matches('v'))
+                        .ok()
+                        .filter(|version| req.matches(version))
+                        .map(|version| (version, tag))
+                })
+                .max_by(|a, b| a.0.cmp(&b.0))
+                .map(|(_, tag)| tag)
+                .ok_or_else(|| {
+                    crate::Error::new(format!(
+                        "No cached template tag found matching the range: {}, run without --offline to fetch it",
+                        version_req
+                    ))
+                });
         }
 
-        Ok(clone_dir)
+        let (tag, commit) = find_highest_matching_tag(&git::ls_remote_tags(repo_url).wrap()?, &req)
+            .ok_or_else(|| crate::Error::new(format!("No template tag found matching the range: {}", version_req)))?;
+
+        progress.println(format!(
+            "Resolved {} to tag {} (commit {})",
+            version_req.green(),
+            tag.as_str().green(),
+            &commit[..commit.len().min(7)]
+        ));
+
+        Ok(tag)
     }
 
-    fn gen_source_code<T: AsRef<Path>, P: AsRef<Path>>(&self, template_dir: T, project_dir: P) -> crate::Result<()> {
+    /// Generates the project files from the template dir and returns the template data used.
+    pub fn gen_source_code<T: AsRef<Path>, P: AsRef<Path>>(
+        &self,
+        template_dir: T,
+        project_dir: P,
+        options: &GenerateOptions,
+    ) -> crate::Result<HashMap<String, Value>> {
         let template_dir = template_dir.as_ref();
         let project_dir = project_dir.as_ref();
 
         let mut template_config = self.extract_template_config(template_dir)?;
         self.resolve_template_config(&mut template_config, template_dir, project_dir)?;
+        apply_default_answers(&mut template_config, &options.default_data);
 
         let template_source_dir = template_dir
             .join(template_config.template.path.as_str())
@@ -162,8 +647,115 @@ impl ProjectTemplate {
             ))
         })?;
 
-        let template_data =
-            data_prompts::ask_data(&template_config).context("Failed to get template data from the user")?;
+        let template_data = data_prompts::ask_data(&template_config, &options.data, options.use_defaults)
+            .context("Failed to get template data from the user")?;
+
+        let plan = self.plan_source_code(
+            &template_config,
+            template_source_dir.as_path(),
+            project_dir,
+            &template_engine,
+            &template_data,
+            options.on_conflict,
+        )?;
+
+        let used_template_data = template_data
+            .iter()
+            .map(|(name, val)| (name.to_string(), val.clone()))
+            .collect();
+
+        if options.dry_run {
+            plan.print(project_dir);
+            return Ok(used_template_data);
+        }
+
+        plan.ensure_no_collisions()?;
+        plan.ensure_no_failing_conflicts()?;
+
+        // All the files are staged first and moved into the project dir only if every file is generated.
+        let mut transaction = Transaction::new(project_dir)?;
+
+        if options.render_only {
+            for entry in plan.files() {
+                self.gen_a_single_code_file(
+                    &mut transaction,
+                    project_dir,
+                    entry,
+                    &template_engine,
+                    &template_data,
+                    false,
+                )?;
+            }
+
+            transaction.commit()?;
+            return Ok(used_template_data);
+        }
+
+        println!();
+
+        for entry in plan.files() {
+            self.gen_a_single_code_file(
+                &mut transaction,
+                project_dir,
+                entry,
+                &template_engine,
+                &template_data,
+                true,
+            )?;
+        }
+
+        self.save_answers(&mut transaction, template_dir, &template_config, &used_template_data)?;
+
+        transaction.commit()?;
+
+        if !options.skip_git_init {
+            git::init(project_dir).wrap()?;
+        }
+
+        println!();
+
+        if let Some(ref val) = template_config.post_generate {
+            handle_post_generate_command(
+                val,
+                template_dir,
+                template_source_dir.as_path(),
+                project_dir,
+                &template_data,
+            )?;
+        }
+
+        println!(
+            "\nSuccess! Created {} at {}\n",
+            project_dir.file_name().and_then(|p| p.to_str()).unwrap_or("").green(),
+            project_dir.to_str().unwrap_or("").green()
+        );
+
+        if let Some(ref val) = template_config.help_text {
+            handle_post_generate_help_text(
+                val,
+                template_dir,
+                template_source_dir.as_path(),
+                project_dir,
+                &template_data,
+                &template_engine,
+            )?;
+            println!();
+        }
+
+        Ok(used_template_data)
+    }
+
+    fn plan_source_code(
+        &self,
+        template_config: &TemplateConfig,
+        template_source_dir: &Path,
+        project_dir: &Path,
+        template_engine: &TemplateEngine,
+        template_data: &HashMap<&str, Value>,
+        conflict_strategy: Option<ConflictStrategy>,
+    ) -> crate::Result<GenerationPlan> {
+        let template_meta = &template_config.template;
+        let entries = RefCell::new(Vec::new());
 
         let boilerplato_ignore_file_path = template_source_dir.join(constants::TEMPLATE_BOILERPLATO_IGNORE_FILE_NAME);
         let boilerplato_ignore_file_holder = {
@@ -176,7 +768,7 @@ impl ProjectTemplate {
 
         let git_ignore_file_path = template_source_dir.join(constants::TEMPLATE_GIT_IGNORE_FILE_NAME);
         let git_ignore_file_holder = {
-            if let (ProjectTemplate::Local(_), true) = (self, git_ignore_file_path.exists()) {
+            if let (TemplateSource::Local(_), true) = (&self.source, git_ignore_file_path.exists()) {
                 gitignore::File::new(git_ignore_file_path.as_path()).ok()
             } else {
                 None
@@ -191,7 +783,7 @@ impl ProjectTemplate {
             }
 
             if let Some(ref f) = boilerplato_ignore_file_holder {
-                if let Some(ignored) = f.is_excluded(entry_full_path).ok() {
+                if let Ok(ignored) = f.is_excluded(entry_full_path) {
                     if ignored {
                         return Ok(true);
                     }
@@ -199,7 +791,7 @@ impl ProjectTemplate {
             }
 
             if let Some(ref f) = git_ignore_file_holder {
-                if let Some(ignored) = f.is_excluded(entry_full_path).ok() {
+                if let Ok(ignored) = f.is_excluded(entry_full_path) {
                     if ignored {
                         return Ok(true);
                     }
@@ -207,12 +799,12 @@ impl ProjectTemplate {
             }
 
             if let Some(ref files_map) = template_config.files_map {
-                if let Ok(entry_rel_path) = entry_full_path.strip_prefix(template_source_dir.as_path()) {
+                if let Ok(entry_rel_path) = entry_full_path.strip_prefix(template_source_dir) {
                     let config = entry_rel_path.to_str().and_then(|s| files_map.get(&s.to_string()));
 
                     if let Some(config) = config {
                         let check = template_engine
-                            .render_template(config.check.as_str(), &template_data)
+                            .render_template(config.check.as_str(), template_data)
                             .context(format!(
                                 "Couldn't generate check condition for key in 'files' attribute: {}",
                                 entry_rel_path.to_str().unwrap_or("")
@@ -230,7 +822,7 @@ impl ProjectTemplate {
                 let template_versioned_of_file_exists = entry_full_path
                     .to_str()
                     .map(|p| format!("{}{}", p, template_meta.extension))
-                    .map(|p| PathBuf::from(p))
+                    .map(PathBuf::from)
                     .map(|p| p.exists());
 
                 if let Some(exists) = template_versioned_of_file_exists {
@@ -240,29 +832,57 @@ impl ProjectTemplate {
                 }
             }
 
-            return Ok(false);
+            Ok(false)
         };
 
-        println!();
+        let recording_ignore_checker = |entry_full_path: &Path| {
+            let ignored = ignore_checker(entry_full_path)?;
+
+            let is_always_ignored = entry_full_path
+                .file_name()
+                .map(|file_name| constants::TEMPLATE_IGNORED_FILES.contains(&file_name))
+                .unwrap_or(false);
+
+            if ignored && !is_always_ignored {
+                entries.borrow_mut().push(PlanEntry {
+                    action: PlanAction::Skip,
+                    source_full_path: entry_full_path.to_path_buf(),
+                    source_rel_path: entry_full_path
+                        .strip_prefix(template_source_dir)
+                        .unwrap_or(entry_full_path)
+                        .to_path_buf(),
+                    target_rel_path: None,
+                    target_exists: false,
+                    conflict_strategy: ConflictStrategy::default(),
+                });
+            }
 
-        // println!(
-        //     "\nCreating a new app in {}\n",
-        //     project_dir.to_str().unwrap_or("").green()
-        // );
+            Ok(ignored)
+        };
 
         self.walk_template_dir(
-            template_source_dir.as_path(),
-            &ignore_checker,
+            template_source_dir,
+            &recording_ignore_checker,
             &|entry_full_path, rel_path_in_project_dir| {
-                self.gen_a_single_code_file(
-                    template_source_dir.as_path(),
-                    project_dir,
-                    entry_full_path,
-                    rel_path_in_project_dir,
-                    template_meta.extension.as_str(),
-                    &template_engine,
-                    &template_data,
-                )?;
+                let is_template_file = entry_full_path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .and_then(|ext| utils::or(ext == &template_meta.extension[1..], Some(()), None))
+                    .is_some();
+
+                entries.borrow_mut().push(PlanEntry {
+                    action: utils::or(is_template_file, PlanAction::Render, PlanAction::Copy),
+                    source_full_path: entry_full_path.to_path_buf(),
+                    source_rel_path: entry_full_path
+                        .strip_prefix(template_source_dir)
+                        .unwrap_or(entry_full_path)
+                        .to_path_buf(),
+                    target_rel_path: Some(rel_path_in_project_dir.to_path_buf()),
+                    target_exists: project_dir.join(rel_path_in_project_dir).exists(),
+                    conflict_strategy: conflict_strategy
+                        .or_else(|| template_config.conflict_strategy_for(rel_path_in_project_dir))
+                        .unwrap_or_default(),
+                });
                 Ok(())
             },
             &|entry_rel_path, entry_file_name, file_type| {
@@ -272,7 +892,7 @@ impl ProjectTemplate {
 
                         if let Some(new_name_template) = new_name_template {
                             return template_engine
-                                .render_template(new_name_template.as_str(), &template_data)
+                                .render_template(new_name_template.as_str(), template_data)
                                 .context(format!(
                                     "Couldn't generate new name for key in 'files' attribute: {}",
                                     rel_path_str
@@ -295,43 +915,18 @@ impl ProjectTemplate {
 
                 Ok(entry_file_name_without_template_extension.to_string())
             },
-            template_source_dir.as_path(),
+            template_source_dir,
             Path::new(""),
         )?;
 
-        self.initialize_git_to_project_dir(project_dir)?;
-
-        println!();
-
-        if let Some(ref val) = template_config.post_generate {
-            handle_post_generate_command(
-                val,
-                template_dir,
-                template_source_dir.as_path(),
-                project_dir,
-                &template_data,
-            )?;
-        }
-
-        println!(
-            "\nSuccess! Created {} at {}\n",
-            project_dir.file_name().and_then(|p| p.to_str()).unwrap_or("").green(),
-            project_dir.to_str().unwrap_or("").green()
-        );
-
-        if let Some(ref val) = template_config.help_text {
-            handle_post_generate_help_text(
-                val,
-                template_dir,
-                template_source_dir.as_path(),
-                project_dir,
-                &template_data,
-                &template_engine,
-            )?;
-            println!();
-        }
-
-        Ok(())
+        Ok(GenerationPlan {
+            entries: entries.into_inner(),
+            post_generate_commands: template_config
+                .post_generate
+                .as_ref()
+                .map(resolve_post_generate_commands)
+                .unwrap_or_default(),
+        })
     }
 
     fn resolve_template_config(
@@ -368,7 +963,7 @@ impl ProjectTemplate {
                         values: None,
                         required: false,
                         default_value: Some(Value::String(app_name.to_owned())),
-                        message: format!("Enter app name: "),
+                        message: "Enter app name: ".to_string(),
                     },
                 );
             }
@@ -385,14 +980,15 @@ impl ProjectTemplate {
             .iter_mut()
             .filter(|d| d.data_type == TemplateDataType::String && !d.required)
             .for_each(|d| {
-                d.default_value
+                let substituted = d
+                    .default_value
                     .as_ref()
                     .and_then(|val| val.as_str())
-                    .map(|s| substitute_variable_in_text(s, &HashMap::new(), &extra_data))
-                    .and_then(|val| {
-                        d.default_value = Some(Value::String(val));
-                        Some(())
-                    });
+                    .map(|s| substitute_variable_in_text(s, &HashMap::new(), &extra_data));
+
+                if let Some(val) = substituted {
+                    d.default_value = Some(Value::String(val));
+                }
             });
 
         if let Some(ref files) = template_config.files {
@@ -456,108 +1052,165 @@ impl ProjectTemplate {
 
     fn gen_a_single_code_file(
         &self,
-        _template_source_dir: &Path,
+        transaction: &mut Transaction,
         project_dir: &Path,
-        template_file_full_path: &Path,
-        template_file_rel_path_in_project_dir: &Path,
-        template_file_extension: &str,
+        entry: &PlanEntry,
         template_engine: &TemplateEngine,
         template_data: &HashMap<&str, Value>,
+        verbose: bool,
     ) -> crate::Result<()> {
-        let is_template_file = template_file_full_path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .and_then(|ext| utils::or(ext == &template_file_extension[1..], Some(()), None))
-            .is_some();
-
-        println!(
-            "{} {}",
-            "Generating".green(),
-            template_file_rel_path_in_project_dir.to_str().unwrap_or("")
-        );
-
-        let project_actual_file_path = project_dir.join(template_file_rel_path_in_project_dir);
-        if let Some(parent) = project_actual_file_path.parent() {
-            fs::create_dir_all(parent).context(format!(
-                "Couldn't create the missing project dir: {}",
-                parent.to_str().unwrap_or("")
-            ))?;
-        }
-
-        if project_actual_file_path.exists() {
-            project_actual_file_path
-                .to_str()
-                .map(|path| PathBuf::from(format!("{}.old", path)))
-                .and_then(|new_path| {
-                    fs::rename(project_actual_file_path.as_path(), new_path.as_path())
-                        .ok()
-                        .map(|_| new_path)
-                })
-                .and_then(|new_path| {
-                    println!(
-                        "{} You had a `{}` file, we renamed it to `{}`",
-                        "Warning:".yellow(),
-                        project_actual_file_path
-                            .strip_prefix(project_dir)
-                            .ok()
-                            .and_then(|rel_path| rel_path.to_str())
-                            .unwrap_or(""),
-                        new_path
-                            .strip_prefix(project_dir)
-                            .ok()
-                            .and_then(|rel_path| rel_path.to_str())
-                            .unwrap_or("")
-                    );
-                    Some(())
-                });
-        }
+        let template_file_full_path = entry.source_full_path.as_path();
+        let template_file_rel_path_in_project_dir = match entry.target_rel_path {
+            Some(ref path) => path.as_path(),
+            None => return Ok(()),
+        };
+        let rel_path_str = template_file_rel_path_in_project_dir.to_str().unwrap_or("");
 
-        if !is_template_file {
-            fs::copy(template_file_full_path, project_actual_file_path.as_path()).context(format!(
-                "Failed to copy the template file: {} to project file: {}",
-                template_file_full_path.to_str().unwrap_or(""),
-                project_actual_file_path.to_str().unwrap_or("")
-            ))?;
+        let generated_code = if entry.action == PlanAction::Copy {
+            fs::read(template_file_full_path).context(format!(
+                "Couldn't read the template file: {}",
+                template_file_full_path.to_str().unwrap_or("")
+            ))?
         } else {
             let template_text = fs::read_to_string(template_file_full_path).context(format!(
                 "Couldn't read the template file: {}",
                 template_file_full_path.to_str().unwrap_or("")
             ))?;
 
-            let actual_code = template_engine
+            template_engine
                 .render_template(template_text.as_str(), template_data)
                 .context(format!(
                     "Failed to generate actual code from template file: {}",
                     template_file_full_path.to_str().unwrap_or("")
-                ))?;
+                ))?
+                .into_bytes()
+        };
+
+        let project_actual_file_path = project_dir.join(template_file_rel_path_in_project_dir);
+        let mut backup = false;
 
-            fs::write(project_actual_file_path.as_path(), actual_code.as_bytes()).context(format!(
-                "Can't write generated code to project file: {}",
+        if project_actual_file_path.is_file() {
+            let existing_code = fs::read(project_actual_file_path.as_path()).context(format!(
+                "Couldn't read the existing project file: {}",
                 project_actual_file_path.to_str().unwrap_or("")
             ))?;
+
+            if existing_code == generated_code {
+                if verbose {
+                    println!("{} {}", "Unchanged".bright_black(), rel_path_str);
+                }
+                return Ok(());
+            }
+
+            let mut conflict_strategy = entry.conflict_strategy;
+            if conflict_strategy == ConflictStrategy::Prompt {
+                conflict_strategy = conflict::ask_conflict_strategy(
+                    template_file_rel_path_in_project_dir,
+                    existing_code.as_slice(),
+                    generated_code.as_slice(),
+                )?;
+            }
+
+            match conflict_strategy {
+                ConflictStrategy::Skip => {
+                    println!("{} {} (kept the existing file)", "Skipping".yellow(), rel_path_str);
+                    return Ok(());
+                }
+                ConflictStrategy::Fail => {
+                    return Err(crate::Error::new(format!(
+                        "The project file already exists: {}",
+                        rel_path_str
+                    )));
+                }
+                ConflictStrategy::Backup => {
+                    backup = true;
+
+                    println!(
+                        "{} You had a `{}` file, we renamed it to `{}{}`",
+                        "Warning:".yellow(),
+                        rel_path_str,
+                        rel_path_str,
+                        constants::CONFLICT_BACKUP_FILE_EXTENSION
+                    );
+                }
+                ConflictStrategy::Overwrite | ConflictStrategy::Prompt => {
+                    println!("{} {}", "Overwriting".yellow(), rel_path_str);
+                }
+            }
         }
 
-        Ok(())
+        if verbose {
+            println!("{} {}", "Generating".green(), rel_path_str);
+        }
+
+        let permissions = if entry.action == PlanAction::Copy {
+            let metadata = fs::metadata(template_file_full_path).context(format!(
+                "Couldn't read the template file permissions: {}",
+                template_file_full_path.to_str().unwrap_or("")
+            ))?;
+            Some(metadata.permissions())
+        } else {
+            None
+        };
+
+        transaction.stage_file(
+            template_file_rel_path_in_project_dir,
+            generated_code.as_slice(),
+            permissions,
+            backup,
+        )
     }
 
-    fn initialize_git_to_project_dir(&self, project_dir: &Path) -> crate::Result<()> {
-        let output = Command::new("git")
-            .arg("init")
-            .current_dir(project_dir)
-            .output()
-            .context("The 'git' command not found")?;
+    pub fn save_answers(
+        &self,
+        transaction: &mut Transaction,
+        template_dir: &Path,
+        template_config: &TemplateConfig,
+        template_data: &HashMap<String, Value>,
+    ) -> crate::Result<()> {
+        // The local template path is recorded as absolute so that it can be found from the project dir.
+        let source = match self.source {
+            TemplateSource::Local(ref path) | TemplateSource::LocalArchive(ref path) => ProjectTemplate {
+                source: TemplateSource::Local(
+                    utils::absolute_path(Path::new(path))
+                        .ok()
+                        .and_then(|path| path.to_str().map(|p| p.to_owned()))
+                        .unwrap_or_else(|| path.to_owned()),
+                ),
+                git_ref: self.git_ref.clone(),
+                version_req: None,
+                subdir: self.subdir.clone(),
+            }
+            .to_string(),
+            _ => utils::redact_credentials(self.to_string().as_str()),
+        };
 
-        if !output.status.success() {
-            return Err(crate::Error::new(format!(
-                "Failed to initialize git to the project dir: {}",
-                String::from_utf8(output.stderr).unwrap_or(String::new())
-            )));
-        }
+        let data = template_config
+            .data
+            .iter()
+            .filter(|d| d.data_type != TemplateDataType::Secret && d.name != constants::TEMPLATE_DATA_APP_NAME)
+            .filter_map(|d| template_data.get(&d.name).map(|val| (d.name.clone(), val.clone())))
+            .collect();
+
+        let answers = AnswersFile {
+            boilerplato_version: constants::APP_VERSION.to_owned(),
+            template: TemplateProvenance {
+                source,
+                tag: git::head_tag(template_dir),
+                commit: git::head_commit(template_dir),
+            },
+            data,
+        };
 
-        Ok(())
+        transaction.stage_file(
+            Path::new(constants::PROJECT_ANSWERS_FILE_PATH),
+            answers.to_yaml()?.as_bytes(),
+            None,
+            false,
+        )
     }
 
-    fn extract_template_config<T: AsRef<Path>>(&self, template_dir: T) -> crate::Result<TemplateConfig> {
+    pub fn extract_template_config<T: AsRef<Path>>(&self, template_dir: T) -> crate::Result<TemplateConfig> {
         let json_file = constants::TEMPLATE_CONFIG_FILE_JSON;
         let yaml_file = constants::TEMPLATE_CONFIG_FILE_YAML;
         let yml_file = constants::TEMPLATE_CONFIG_FILE_YML;
@@ -645,31 +1298,490 @@ impl ProjectTemplate {
     }
 }
 
+impl Display for ProjectTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)?;
+
+        if let Some(ref subdir) = self.subdir {
+            write!(f, "{}{}", self.source.subdir_separator(), subdir)?;
+        }
+
+        if let Some(ref version_req) = self.version_req {
+            write!(f, "{}{}", constants::TEMPLATE_VERSION_SEPARATOR, version_req)?;
+        }
+
+        if let Some(ref git_ref) = self.git_ref {
+            write!(f, "{}{}", constants::TEMPLATE_REF_SEPARATOR, git_ref)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TemplateSource {
+    fn subdir_separator(&self) -> &str {
+        match self {
+            TemplateSource::Github(_, _) => "/",
+            _ => constants::TEMPLATE_SUBDIR_SEPARATOR,
+        }
+    }
+}
+
+impl Display for TemplateSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateSource::InBuilt(ref name) => write!(f, "{}", name),
+            TemplateSource::Github(ref handle, ref repo) => write!(f, "{}/{}", handle, repo),
+            TemplateSource::Forge(ref prefix, ref path) => write!(f, "{}:{}", prefix, path),
+            TemplateSource::Local(ref path) => write!(f, "{}{}", constants::TEMPLATE_PREFIX_FILE, path),
+            TemplateSource::AnyGitRepo(ref url) => write!(f, "{}", url),
+            TemplateSource::LocalArchive(ref path) => write!(f, "{}{}", constants::TEMPLATE_PREFIX_FILE, path),
+            TemplateSource::RemoteArchive(ref url) => write!(f, "{}", url),
+            TemplateSource::GitUrl(ref url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// Replaces the template's default values with the user's default answers, the answers which are invalid for
+/// the template data are ignored.
+fn apply_default_answers(template_config: &mut TemplateConfig, default_answers: &HashMap<String, Value>) {
+    for data in template_config.data.iter_mut() {
+        if data.name == constants::TEMPLATE_DATA_APP_NAME {
+            continue;
+        }
+
+        if let Some(answer) = default_answers.get(&data.name) {
+            match data_prompts::validate_value(data, answer) {
+                Ok(answer) => {
+                    data.default_value = Some(answer);
+                    data.required = false;
+                }
+                Err(err) => println!(
+                    "{} Ignoring the default answer of `{}` from the user config: {}",
+                    "Warning:".yellow(),
+                    data.name,
+                    err
+                ),
+            }
+        }
+    }
+}
+
+/// Checks for the scp-style git URLs e.g. `git@github.com:org/repo.git` or `github.com:org/repo.git`, the
+/// host must have a user or a domain to not be confused with the other `<prefix>:` specifiers.
+fn is_scp_like_git_url(template: &str) -> bool {
+    match template.split_once(':') {
+        Some((user_host, path)) => {
+            let host = user_host.rsplit('@').next().unwrap_or("");
+
+            !user_host.contains('/')
+                && !host.is_empty()
+                && !path.is_empty()
+                && !path.starts_with("//")
+                && (user_host.contains('@') || host.contains('.'))
+        }
+        None => false,
+    }
+}
+
+/// Splits a forge prefix off a specifier e.g. `gitlab` in `gitlab:group/repo`, the prefix is a name with
+/// at least two characters to not be confused with a Windows drive.
+fn split_forge_prefix(template: &str) -> Option<(&str, &str)> {
+    template.split_once(':').filter(|(prefix, path)| {
+        prefix.len() > 1
+            && !path.is_empty()
+            && prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+/// Splits the `//` separated subdirectory off a repo URL or a local path, the separator is searched from the
+/// given index to skip the URL scheme.
+fn split_subdir(location: &str, search_from: usize) -> (&str, Option<&str>) {
+    let idx = location
+        .get(search_from..)
+        .and_then(|rest| rest.find(constants::TEMPLATE_SUBDIR_SEPARATOR))
+        .map(|idx| idx + search_from);
+
+    match idx {
+        Some(idx) => {
+            let subdir = location[idx..].trim_matches('/');
+            (&location[..idx], Some(subdir).filter(|subdir| !subdir.is_empty()))
+        }
+        None => (location, None),
+    }
+}
+
+/// Picks the highest tag matching the range from the remote tag refs and their commits, returns the tag and its
+/// commit. The tags can have a `v` prefix e.g. `v2.1.0`.
+fn find_highest_matching_tag(tag_refs: &[(String, String)], req: &VersionReq) -> Option<(String, String)> {
+    let mut commits = HashMap::new();
+
+    for (ref_name, commit) in tag_refs {
+        let tag = match ref_name.strip_prefix("refs/tags/") {
+            Some(tag) => tag,
+            None => continue,
+        };
+
+        // The annotated tags are listed twice, the peeled `^{}` entry points to the tagged commit.
+        match tag.strip_suffix("^{}") {
+            Some(tag) => {
+                commits.insert(tag.to_owned(), commit.to_owned());
+            }
+            None => {
+                commits.entry(tag.to_owned()).or_insert_with(|| commit.to_owned());
+            }
+        }
+    }
+
+    commits
+        .into_iter()
+        .filter_map(|(tag, commit)| {
+            Version::parse(tag.trim_start_matches('v'))
+                .ok()
+                .filter(|version| req.matches(version))
+                .map(|version| (version, tag, commit))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, tag, commit)| (tag, commit))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn template(source: TemplateSource, git_ref: Option<&str>) -> ProjectTemplate {
+        ProjectTemplate {
+            source,
+            git_ref: git_ref.map(|r| r.to_owned()),
+            version_req: None,
+            subdir: None,
+        }
+    }
+
     #[test]
     fn test_project_template_parse() {
         assert_eq!(
             ProjectTemplate::parse("react-redux-router-nodejs"),
-            ProjectTemplate::InBuilt("react-redux-router-nodejs".to_owned())
+            template(TemplateSource::InBuilt("react-redux-router-nodejs".to_owned()), None)
         );
         assert_eq!(
             ProjectTemplate::parse("john/bar"),
-            ProjectTemplate::Github("john".to_owned(), "bar".to_owned()),
+            template(TemplateSource::Github("john".to_owned(), "bar".to_owned()), None),
         );
         assert_eq!(
             ProjectTemplate::parse("file:../abc"),
-            ProjectTemplate::Local("../abc".to_owned()),
+            template(TemplateSource::Local("../abc".to_owned()), None),
         );
         assert_eq!(
             ProjectTemplate::parse("https://github.com/foo/bar.git"),
-            ProjectTemplate::AnyGitRepo("https://github.com/foo/bar.git".to_owned()),
+            template(
+                TemplateSource::AnyGitRepo("https://github.com/foo/bar.git".to_owned()),
+                None
+            ),
         );
         assert_eq!(
             ProjectTemplate::parse("http://github.com/foo/bar.git"),
-            ProjectTemplate::AnyGitRepo("http://github.com/foo/bar.git".to_owned()),
+            template(
+                TemplateSource::AnyGitRepo("http://github.com/foo/bar.git".to_owned()),
+                None
+            ),
+        );
+    }
+
+    #[test]
+    fn test_project_template_parse_git_url() {
+        assert_eq!(
+            ProjectTemplate::parse("git@github.com:org/repo.git"),
+            template(TemplateSource::GitUrl("git@github.com:org/repo.git".to_owned()), None),
+        );
+        assert_eq!(
+            ProjectTemplate::parse("github.com:org/repo.git#v1"),
+            template(TemplateSource::GitUrl("github.com:org/repo.git".to_owned()), Some("v1")),
+        );
+        assert_eq!(
+            ProjectTemplate::parse("ssh://git@example.com:2222/org/repo.git"),
+            template(
+                TemplateSource::GitUrl("ssh://git@example.com:2222/org/repo.git".to_owned()),
+                None
+            ),
+        );
+        assert_eq!(
+            ProjectTemplate::parse("git://example.com/org/repo.git"),
+            template(
+                TemplateSource::GitUrl("git://example.com/org/repo.git".to_owned()),
+                None
+            ),
+        );
+        assert_eq!(
+            ProjectTemplate::parse("git+ssh://git@example.com/org/repo.git"),
+            template(
+                TemplateSource::GitUrl("git+ssh://git@example.com/org/repo.git".to_owned()),
+                None
+            ),
+        );
+
+        let parsed = ProjectTemplate::parse("git@github.com:org/monorepo.git//templates/web");
+        assert_eq!(
+            parsed.source,
+            TemplateSource::GitUrl("git@github.com:org/monorepo.git".to_owned())
+        );
+        assert_eq!(parsed.subdir, Some("templates/web".to_owned()));
+        assert_eq!(parsed.to_string(), "git@github.com:org/monorepo.git//templates/web");
+    }
+
+    #[test]
+    fn test_project_template_parse_archive() {
+        assert_eq!(
+            ProjectTemplate::parse("file:../template.tar.gz"),
+            template(TemplateSource::LocalArchive("../template.tar.gz".to_owned()), None),
+        );
+        assert_eq!(
+            ProjectTemplate::parse("https://example.com/releases/template-1.0.0.zip"),
+            template(
+                TemplateSource::RemoteArchive("https://example.com/releases/template-1.0.0.zip".to_owned()),
+                None
+            ),
+        );
+
+        let parsed = ProjectTemplate::parse("https://example.com/templates.tgz//web");
+        assert_eq!(
+            parsed.source,
+            TemplateSource::RemoteArchive("https://example.com/templates.tgz".to_owned())
+        );
+        assert_eq!(parsed.subdir, Some("web".to_owned()));
+
+        assert_eq!(
+            ProjectTemplate::parse("https://example.com/t.tar.gz?token=abc").source,
+            TemplateSource::RemoteArchive("https://example.com/t.tar.gz?token=abc".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_project_template_parse_forge() {
+        assert_eq!(
+            ProjectTemplate::parse("gitlab:group/subgroup/repo"),
+            template(
+                TemplateSource::Forge("gitlab".to_owned(), "group/subgroup/repo".to_owned()),
+                None
+            ),
+        );
+        assert_eq!(
+            ProjectTemplate::parse("bitbucket:owner/repo#v1"),
+            template(
+                TemplateSource::Forge("bitbucket".to_owned(), "owner/repo".to_owned()),
+                Some("v1")
+            ),
         );
+        assert_eq!(
+            ProjectTemplate::parse("sourcehut:~user/repo"),
+            template(
+                TemplateSource::Forge("sourcehut".to_owned(), "~user/repo".to_owned()),
+                None
+            ),
+        );
+
+        let parsed = ProjectTemplate::parse("corp:team/templates//web@^1.0");
+        assert_eq!(
+            parsed.source,
+            TemplateSource::Forge("corp".to_owned(), "team/templates".to_owned())
+        );
+        assert_eq!(parsed.subdir, Some("web".to_owned()));
+        assert_eq!(parsed.version_req, Some("^1.0".to_owned()));
+        assert_eq!(parsed.to_string(), "corp:team/templates//web@^1.0");
+    }
+
+    #[test]
+    fn test_project_template_parse_git_ref() {
+        assert_eq!(
+            ProjectTemplate::parse("john/bar#v2.1.0"),
+            template(
+                TemplateSource::Github("john".to_owned(), "bar".to_owned()),
+                Some("v2.1.0")
+            ),
+        );
+        assert_eq!(
+            ProjectTemplate::parse("john/bar#4f3c2a1"),
+            template(
+                TemplateSource::Github("john".to_owned(), "bar".to_owned()),
+                Some("4f3c2a1")
+            ),
+        );
+        assert_eq!(
+            ProjectTemplate::parse("https://github.com/foo/bar.git#develop"),
+            template(
+                TemplateSource::AnyGitRepo("https://github.com/foo/bar.git".to_owned()),
+                Some("develop")
+            ),
+        );
+        assert_eq!(
+            ProjectTemplate::parse("john/bar#"),
+            template(TemplateSource::Github("john".to_owned(), "bar".to_owned()), None),
+        );
+        assert_eq!(ProjectTemplate::parse("john/bar#v2.1.0").to_string(), "john/bar#v2.1.0");
+    }
+
+    #[test]
+    fn test_project_template_parse_version_req() {
+        let parsed = ProjectTemplate::parse("john/bar@^2.0");
+        assert_eq!(
+            parsed.source,
+            TemplateSource::Github("john".to_owned(), "bar".to_owned())
+        );
+        assert_eq!(parsed.version_req, Some("^2.0".to_owned()));
+        assert_eq!(parsed.to_string(), "john/bar@^2.0");
+
+        let parsed = ProjectTemplate::parse("react-template@~1.4");
+        assert_eq!(parsed.source, TemplateSource::InBuilt("react-template".to_owned()));
+        assert_eq!(parsed.version_req, Some("~1.4".to_owned()));
+
+        let parsed = ProjectTemplate::parse("https://john@example.com/foo/bar.git");
+        assert_eq!(parsed.version_req, None);
+    }
+
+    #[test]
+    fn test_project_template_parse_subdir() {
+        let parsed = ProjectTemplate::parse("john/monorepo/templates/web@^1.2#main");
+        assert_eq!(
+            parsed.source,
+            TemplateSource::Github("john".to_owned(), "monorepo".to_owned())
+        );
+        assert_eq!(parsed.subdir, Some("templates/web".to_owned()));
+        assert_eq!(parsed.version_req, Some("^1.2".to_owned()));
+        assert_eq!(parsed.git_ref, Some("main".to_owned()));
+        assert_eq!(parsed.to_string(), "john/monorepo/templates/web@^1.2#main");
+
+        let parsed = ProjectTemplate::parse("https://example.com/x.git//sub/dir#v1");
+        assert_eq!(
+            parsed.source,
+            TemplateSource::AnyGitRepo("https://example.com/x.git".to_owned())
+        );
+        assert_eq!(parsed.subdir, Some("sub/dir".to_owned()));
+        assert_eq!(parsed.to_string(), "https://example.com/x.git//sub/dir#v1");
+
+        let parsed = ProjectTemplate::parse("file:../repo//sub");
+        assert_eq!(parsed.source, TemplateSource::Local("../repo".to_owned()));
+        assert_eq!(parsed.subdir, Some("sub".to_owned()));
+
+        let parsed = ProjectTemplate::parse("https://example.com/x.git");
+        assert_eq!(parsed.subdir, None);
+    }
+
+    #[test]
+    fn test_find_highest_matching_tag() {
+        let output = [
+            ("refs/tags/v1.4.2", "1111111111111111111111111111111111111111"),
+            ("refs/tags/v2.0.0", "2222222222222222222222222222222222222222"),
+            ("refs/tags/v2.3.1", "3333333333333333333333333333333333333333"),
+            ("refs/tags/v2.3.1^{}", "4444444444444444444444444444444444444444"),
+            ("refs/tags/v3.0.0", "5555555555555555555555555555555555555555"),
+            ("refs/tags/2.4.0-beta.1", "6666666666666666666666666666666666666666"),
+            ("refs/tags/nightly", "7777777777777777777777777777777777777777"),
+        ]
+        .iter()
+        .map(|(ref_name, commit)| (ref_name.to_string(), commit.to_string()))
+        .collect::<Vec<(String, String)>>();
+
+        assert_eq!(
+            find_highest_matching_tag(&output, &VersionReq::parse("^2.0").unwrap()),
+            Some((
+                "v2.3.1".to_owned(),
+                "4444444444444444444444444444444444444444".to_owned()
+            ))
+        );
+        assert_eq!(
+            find_highest_matching_tag(&output, &VersionReq::parse("~1.4").unwrap()),
+            Some((
+                "v1.4.2".to_owned(),
+                "1111111111111111111111111111111111111111".to_owned()
+            ))
+        );
+        assert_eq!(
+            find_highest_matching_tag(&output, &VersionReq::parse("^4").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_save_answers_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let template_dir = dir.path().join("template");
+        fs::create_dir_all(template_dir.as_path()).unwrap();
+        fs::write(
+            template_dir.join(constants::TEMPLATE_CONFIG_FILE_YML),
+            "\
+version: 1.0.0
+template:
+  engine: handlebars
+data:
+  - name: appName
+    type: string
+    required: false
+  - name: license
+    type: string
+    required: true
+    values: [MIT, Apache-2.0]
+  - name: private
+    type: bool
+    required: false
+  - name: keywords
+    type: array[string]
+    required: true
+  - name: token
+    type: secret
+    required: true
+",
+        )
+        .unwrap();
+        fs::write(
+            template_dir.join("README.md.boiler"),
+            "{{appName}} {{license}} {{private}} {{#each keywords}}{{this}},{{/each}} {{token}}",
+        )
+        .unwrap();
+
+        let template = ProjectTemplate::parse(format!("file:{}", template_dir.to_str().unwrap()));
+        let mut options = GenerateOptions {
+            use_defaults: true,
+            skip_git_init: true,
+            ..GenerateOptions::default()
+        };
+        options.data = vec![
+            ("license", Value::String("Apache-2.0".to_owned())),
+            ("private", Value::Bool(true)),
+            ("keywords", serde_json::json!(["cli", "rust"])),
+            ("token", Value::String("s3cret".to_owned())),
+        ]
+        .into_iter()
+        .map(|(name, val)| (name.to_owned(), val))
+        .collect();
+
+        let first_dir = dir.path().join("first").join("my-app");
+        template
+            .gen_source_code(template_dir.as_path(), first_dir.as_path(), &options)
+            .unwrap();
+
+        let answers = AnswersFile::load(first_dir.join(constants::PROJECT_ANSWERS_FILE_PATH)).unwrap();
+        assert_eq!(
+            answers.data.keys().map(|name| name.as_str()).collect::<Vec<&str>>(),
+            vec!["keywords", "license", "private"]
+        );
+        assert_eq!(answers.template.source, template.to_string());
+
+        // The secret is never saved, it's provided again on the replay.
+        let mut replay_options = options.clone();
+        replay_options.data = answers.data.into_iter().collect();
+        replay_options
+            .data
+            .insert("token".to_owned(), Value::String("s3cret".to_owned()));
+
+        let second_dir = dir.path().join("second").join("my-app");
+        template
+            .gen_source_code(template_dir.as_path(), second_dir.as_path(), &replay_options)
+            .unwrap();
+
+        let readme = fs::read_to_string(first_dir.join("README.md")).unwrap();
+        assert_eq!(readme, "my-app Apache-2.0 true cli,rust, s3cret");
+        assert_eq!(fs::read_to_string(second_dir.join("README.md")).unwrap(), readme);
     }
 }
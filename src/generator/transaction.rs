@@ -0,0 +1,302 @@
+use crate::constants;
+use crate::generator::conflict;
+use crate::prelude::*;
+use crate::utils::interrupt::{self, CriticalSection};
+use crate::utils::{self, TempDir};
+use std::fs::{self, Permissions};
+use std::path::{Path, PathBuf};
+
+const STAGING_FILES_DIR: &str = "files";
+const STAGING_ROLLBACK_DIR: &str = "rollback";
+
+#[derive(Debug)]
+enum StagedChange {
+    Write { rel_path: PathBuf, backup: bool },
+    Remove { rel_path: PathBuf },
+}
+
+#[derive(Debug)]
+enum AppliedChange {
+    CreatedDir(PathBuf),
+    Placed(PathBuf),
+    Moved { from: PathBuf, to: PathBuf },
+}
+
+/// Collects the project file changes in a staging dir next to the project dir and applies them all at once,
+/// the applied changes are rolled back if a change fails or the process is interrupted.
+#[derive(Debug)]
+pub struct Transaction {
+    project_dir: PathBuf,
+    staging_dir: TempDir,
+    changes: Vec<StagedChange>,
+}
+
+impl Transaction {
+    pub fn new(project_dir: &Path) -> crate::Result<Transaction> {
+        let parent_dir = project_dir
+            .parent()
+            .ok_or_else(|| crate::Error::new("The project directory must not be the root directory"))?;
+
+        fs::create_dir_all(parent_dir).context(format!(
+            "Couldn't create the project parent directory: {}",
+            parent_dir.to_str().unwrap_or("")
+        ))?;
+
+        let staging_dir = TempDir::new_at(parent_dir.join(format!(
+            ".{}.{}-{}",
+            project_dir.file_name().and_then(|n| n.to_str()).unwrap_or(""),
+            constants::APP_NAME,
+            utils::gen_uuid()
+        )))?;
+
+        fs::create_dir_all(staging_dir.path().join(STAGING_FILES_DIR))
+            .context("Couldn't create the staging directory")?;
+
+        Ok(Transaction {
+            project_dir: project_dir.to_path_buf(),
+            staging_dir,
+            changes: Vec::new(),
+        })
+    }
+
+    /// Stages a file to be written in the project dir, the existing file is renamed to `<file>.old` if
+    /// `backup` is set, otherwise it's replaced.
+    pub fn stage_file(
+        &mut self,
+        rel_path: &Path,
+        content: &[u8],
+        permissions: Option<Permissions>,
+        backup: bool,
+    ) -> crate::Result<()> {
+        let staged_path = self.staging_dir.path().join(STAGING_FILES_DIR).join(rel_path);
+
+        if let Some(parent) = staged_path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Couldn't create the staging dir: {}",
+                parent.to_str().unwrap_or("")
+            ))?;
+        }
+
+        fs::write(staged_path.as_path(), content).context(format!(
+            "Can't write generated code to the staging file: {}",
+            staged_path.to_str().unwrap_or("")
+        ))?;
+
+        let permissions = permissions.or_else(|| {
+            fs::metadata(self.project_dir.join(rel_path))
+                .ok()
+                .map(|metadata| metadata.permissions())
+        });
+
+        if let Some(permissions) = permissions {
+            fs::set_permissions(staged_path.as_path(), permissions).context(format!(
+                "Couldn't set the permissions of the staging file: {}",
+                staged_path.to_str().unwrap_or("")
+            ))?;
+        }
+
+        self.changes.push(StagedChange::Write {
+            rel_path: rel_path.to_path_buf(),
+            backup,
+        });
+
+        Ok(())
+    }
+
+    pub fn stage_removal(&mut self, rel_path: &Path) {
+        self.changes.push(StagedChange::Remove {
+            rel_path: rel_path.to_path_buf(),
+        });
+    }
+
+    pub fn commit(self) -> crate::Result<()> {
+        let critical_section = CriticalSection::enter();
+        let staged_files_dir = self.staging_dir.path().join(STAGING_FILES_DIR);
+
+        // A new project dir is moved into place at once.
+        if !self.project_dir.exists() && fs::rename(staged_files_dir.as_path(), self.project_dir.as_path()).is_ok() {
+            return critical_section.leave();
+        }
+
+        let mut applied = Vec::with_capacity(self.changes.len());
+        let result = self.apply_changes(staged_files_dir.as_path(), &mut applied);
+
+        if let Err(err) = result {
+            let rollback_result = self.rollback(applied);
+            return match rollback_result {
+                Ok(_) => Err(err.context("All the changes in the project directory are rolled back")),
+                Err(rollback_err) => Err(crate::Error::new(format!(
+                    "{}, and failed to roll back the changes in the project directory: {}",
+                    err, rollback_err
+                ))),
+            };
+        }
+
+        // An interruption after the last change is too late to roll back, but the caller must not go on.
+        critical_section.leave()
+    }
+
+    fn apply_changes(&self, staged_files_dir: &Path, applied: &mut Vec<AppliedChange>) -> crate::Result<()> {
+        self.create_dir_all(self.project_dir.as_path(), applied)?;
+
+        for change in self.changes.iter() {
+            if interrupt::is_interrupted() {
+                return Err(crate::Error::new("Interrupted"));
+            }
+
+            match change {
+                StagedChange::Write { rel_path, backup } => {
+                    let target_path = self.project_dir.join(rel_path);
+
+                    if let Some(parent) = target_path.parent() {
+                        self.create_dir_all(parent, applied)?;
+                    }
+
+                    if target_path.exists() {
+                        if *backup {
+                            let backup_path = conflict::backup_path(target_path.as_path());
+
+                            if backup_path.exists() {
+                                self.stash(backup_path.as_path(), applied)?;
+                            }
+
+                            self.move_file(target_path.as_path(), backup_path.as_path(), applied)?;
+                        } else {
+                            self.stash(target_path.as_path(), applied)?;
+                        }
+                    }
+
+                    let staged_path = staged_files_dir.join(rel_path);
+                    fs::rename(staged_path.as_path(), target_path.as_path()).context(format!(
+                        "Couldn't move the generated file into the project directory: {}",
+                        target_path.to_str().unwrap_or("")
+                    ))?;
+                    applied.push(AppliedChange::Placed(target_path));
+                }
+                StagedChange::Remove { rel_path } => {
+                    let target_path = self.project_dir.join(rel_path);
+                    if target_path.exists() {
+                        self.stash(target_path.as_path(), applied)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rollback(&self, applied: Vec<AppliedChange>) -> crate::Result<()> {
+        for change in applied.into_iter().rev() {
+            match change {
+                AppliedChange::Placed(path) => fs::remove_file(path.as_path()).context(format!(
+                    "Couldn't remove the generated file: {}",
+                    path.to_str().unwrap_or("")
+                ))?,
+                AppliedChange::Moved { from, to } => fs::rename(to.as_path(), from.as_path()).context(format!(
+                    "Couldn't restore the project file: {}",
+                    from.to_str().unwrap_or("")
+                ))?,
+                AppliedChange::CreatedDir(path) => {
+                    let _ = fs::remove_dir(path.as_path());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn stash(&self, path: &Path, applied: &mut Vec<AppliedChange>) -> crate::Result<()> {
+        let rel_path = path.strip_prefix(self.project_dir.as_path()).wrap()?;
+        let stash_path = self.staging_dir.path().join(STAGING_ROLLBACK_DIR).join(rel_path);
+
+        if let Some(parent) = stash_path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Couldn't create the rollback dir: {}",
+                parent.to_str().unwrap_or("")
+            ))?;
+        }
+
+        self.move_file(path, stash_path.as_path(), applied)
+    }
+
+    fn move_file(&self, from: &Path, to: &Path, applied: &mut Vec<AppliedChange>) -> crate::Result<()> {
+        fs::rename(from, to).context(format!(
+            "Couldn't move the project file: {}",
+            from.to_str().unwrap_or("")
+        ))?;
+
+        applied.push(AppliedChange::Moved {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        });
+
+        Ok(())
+    }
+
+    fn create_dir_all(&self, dir: &Path, applied: &mut Vec<AppliedChange>) -> crate::Result<()> {
+        if dir.exists() {
+            return Ok(());
+        }
+
+        if let Some(parent) = dir.parent() {
+            self.create_dir_all(parent, applied)?;
+        }
+
+        fs::create_dir(dir).context(format!(
+            "Couldn't create the missing project dir: {}",
+            dir.to_str().unwrap_or("")
+        ))?;
+        applied.push(AppliedChange::CreatedDir(dir.to_path_buf()));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_rollback() {
+        let root = TempDir::new().unwrap();
+        let project_dir = root.path().join("app");
+        fs::create_dir_all(project_dir.join("src")).unwrap();
+        fs::write(project_dir.join("README.md"), "old readme").unwrap();
+        fs::write(project_dir.join("src/lib.rs"), "old lib").unwrap();
+        fs::write(project_dir.join("LICENSE"), "license").unwrap();
+
+        let mut transaction = Transaction::new(project_dir.as_path()).unwrap();
+        transaction
+            .stage_file(Path::new("README.md"), b"new readme", None, true)
+            .unwrap();
+        transaction
+            .stage_file(Path::new("docs/guide.md"), b"guide", None, false)
+            .unwrap();
+        transaction.stage_removal(Path::new("src/lib.rs"));
+        // A staged file which can't be placed as its parent path is a file.
+        transaction
+            .stage_file(Path::new("LICENSE/nested"), b"nested", None, false)
+            .unwrap();
+
+        assert!(transaction.commit().is_err());
+        assert_eq!(fs::read_to_string(project_dir.join("README.md")).unwrap(), "old readme");
+        assert_eq!(fs::read_to_string(project_dir.join("src/lib.rs")).unwrap(), "old lib");
+        assert!(!project_dir.join("README.md.old").exists());
+        assert!(!project_dir.join("docs").exists());
+
+        let mut transaction = Transaction::new(project_dir.as_path()).unwrap();
+        transaction
+            .stage_file(Path::new("README.md"), b"new readme", None, true)
+            .unwrap();
+        transaction.stage_removal(Path::new("src/lib.rs"));
+
+        assert!(transaction.commit().is_ok());
+        assert_eq!(fs::read_to_string(project_dir.join("README.md")).unwrap(), "new readme");
+        assert_eq!(
+            fs::read_to_string(project_dir.join("README.md.old")).unwrap(),
+            "old readme"
+        );
+        assert!(!project_dir.join("src/lib.rs").exists());
+        assert_eq!(fs::read_dir(root.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,345 @@
+use crate::constants;
+use crate::generator::git;
+use crate::generator::merge::merge_text;
+use crate::generator::options::GenerateOptions;
+use crate::generator::progress::Progress;
+use crate::generator::project_template::ProjectTemplate;
+use crate::generator::transaction::Transaction;
+use crate::prelude::*;
+use crate::types::AnswersFile;
+use crate::utils::TempDir;
+use colored::*;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str;
+
+#[derive(Debug, Default)]
+struct UpdateSummary {
+    updated: Vec<PathBuf>,
+    merged: Vec<PathBuf>,
+    conflicted: Vec<PathBuf>,
+    conflicted_binary: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+    kept: Vec<PathBuf>,
+    skipped: Vec<PathBuf>,
+}
+
+pub fn update_project_from_template<P: AsRef<Path>>(project_dir: P, options: &GenerateOptions) -> crate::Result<()> {
+    let project_dir = project_dir
+        .as_ref()
+        .canonicalize()
+        .context("Couldn't get the absolute project path")?;
+
+    let answers_file_path = project_dir.join(constants::PROJECT_ANSWERS_FILE_PATH);
+    if !answers_file_path.exists() {
+        return Err(crate::Error::new(format!(
+            "No recorded template found in the project, the file {} is missing",
+            constants::PROJECT_ANSWERS_FILE_PATH
+        )));
+    }
+
+    let answers = AnswersFile::load(answers_file_path.as_path())?;
+    let mut template = ProjectTemplate::parse(answers.template.source.as_str());
+    if options.template_ref.is_some() {
+        template.git_ref = options.template_ref.clone();
+    }
+
+    let new_template_dir = template.fetch(None, options.offline, Progress::Stdout)?;
+    let new_template_dir = new_template_dir.path();
+
+    let new_commit = git::head_commit(new_template_dir);
+    if new_commit.is_some() && new_commit == answers.template.commit && options.data.is_empty() {
+        println!(
+            "The project is already up to date with the template: {}",
+            answers.template.source.as_str().green()
+        );
+        return Ok(());
+    }
+
+    let scratch_dir = TempDir::new().context("Couldn't create a temporary folder to update the project")?;
+
+    let result = apply_template_update(
+        &template,
+        &answers,
+        new_template_dir,
+        project_dir.as_path(),
+        scratch_dir.path(),
+        options,
+    );
+
+    // The cleanup is best effort, its failure must not hide the update result.
+    if let Err(err) = scratch_dir.close() {
+        eprintln!("{} {}", "Warning:".yellow(), err);
+    }
+
+    print_summary(&result?);
+
+    Ok(())
+}
+
+/// Renders the recorded and the latest template versions into the scratch dir and merges them into the project.
+fn apply_template_update(
+    template: &ProjectTemplate,
+    answers: &AnswersFile,
+    new_template_dir: &Path,
+    project_dir: &Path,
+    scratch_dir: &Path,
+    options: &GenerateOptions,
+) -> crate::Result<UpdateSummary> {
+    // The scratch dirs are named as the project dir as the template data e.g. appName depends on it.
+    let project_name = project_dir.file_name().unwrap_or_default();
+    let new_output_dir = scratch_dir.join("new").join(project_name);
+    let old_output_dir = scratch_dir.join("old").join(project_name);
+
+    let mut render_options = options.clone();
+    render_options.data = answers.data.clone().into_iter().collect();
+    render_options.data.extend(options.data.clone());
+    render_options.dry_run = false;
+    render_options.render_only = true;
+
+    let template_data = template
+        .gen_source_code(new_template_dir, new_output_dir.as_path(), &render_options)
+        .context("Couldn't generate the new version of the template")?;
+
+    if let Some(ref commit) = answers.template.commit {
+        let old_template_dir = template.fetch(Some(commit.as_str()), options.offline, Progress::Stdout)?;
+
+        render_options.data = template_data.clone();
+        render_options.use_defaults = true;
+
+        template
+            .gen_source_code(old_template_dir.path(), old_output_dir.as_path(), &render_options)
+            .context("Couldn't generate the previous version of the template")?;
+    } else {
+        println!(
+            "{} The template commit wasn't recorded, all the differing files will be marked as conflicts",
+            "Warning:".yellow()
+        );
+    }
+
+    let mut rel_paths = BTreeSet::new();
+    collect_files(new_output_dir.as_path(), Path::new(""), &mut rel_paths)?;
+    collect_files(old_output_dir.as_path(), Path::new(""), &mut rel_paths)?;
+
+    let mut summary = UpdateSummary::default();
+    let mut transaction = Transaction::new(project_dir)?;
+
+    for rel_path in rel_paths {
+        merge_a_single_file(
+            &mut transaction,
+            rel_path.as_path(),
+            old_output_dir.join(rel_path.as_path()).as_path(),
+            new_output_dir.join(rel_path.as_path()).as_path(),
+            project_dir.join(rel_path.as_path()).as_path(),
+            &mut summary,
+        )?;
+    }
+
+    let template_config = template.extract_template_config(new_template_dir)?;
+    template.save_answers(&mut transaction, new_template_dir, &template_config, &template_data)?;
+
+    transaction.commit()?;
+
+    Ok(summary)
+}
+
+fn merge_a_single_file(
+    transaction: &mut Transaction,
+    rel_path: &Path,
+    base_path: &Path,
+    theirs_path: &Path,
+    ours_path: &Path,
+    summary: &mut UpdateSummary,
+) -> crate::Result<()> {
+    let base = read_optional_file(base_path)?;
+    let theirs = read_optional_file(theirs_path)?;
+    let ours = read_optional_file(ours_path)?;
+
+    if base == theirs || ours == theirs {
+        return Ok(());
+    }
+
+    match (base, theirs, ours) {
+        (Some(_), Some(_), None) => summary.skipped.push(rel_path.to_path_buf()),
+        (None, Some(theirs), None) => {
+            transaction.stage_file(rel_path, theirs.as_slice(), None, false)?;
+            summary.updated.push(rel_path.to_path_buf());
+        }
+        (Some(base), None, Some(ours)) => {
+            if base == ours {
+                transaction.stage_removal(rel_path);
+                summary.removed.push(rel_path.to_path_buf());
+            } else {
+                summary.kept.push(rel_path.to_path_buf());
+            }
+        }
+        (base, Some(theirs), Some(ours)) => {
+            if base.as_ref() == Some(&ours) {
+                transaction.stage_file(rel_path, theirs.as_slice(), None, false)?;
+                summary.updated.push(rel_path.to_path_buf());
+                return Ok(());
+            }
+
+            let base = base.unwrap_or_default();
+            match (
+                str::from_utf8(base.as_slice()),
+                str::from_utf8(ours.as_slice()),
+                str::from_utf8(theirs.as_slice()),
+            ) {
+                (Ok(base), Ok(ours), Ok(theirs)) => {
+                    let merged = merge_text(base, ours, theirs);
+                    transaction.stage_file(rel_path, merged.text.as_bytes(), None, false)?;
+
+                    if merged.conflicts > 0 {
+                        summary.conflicted.push(rel_path.to_path_buf());
+                    } else {
+                        summary.merged.push(rel_path.to_path_buf());
+                    }
+                }
+                _ => {
+                    // A binary file can't have conflict markers, the template version is written next to it.
+                    transaction.stage_file(new_file_path(rel_path).as_path(), theirs.as_slice(), None, false)?;
+                    summary.conflicted_binary.push(rel_path.to_path_buf());
+                }
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+fn new_file_path(rel_path: &Path) -> PathBuf {
+    let mut new_path = rel_path.as_os_str().to_owned();
+    new_path.push(constants::UPDATE_NEW_FILE_EXTENSION);
+    PathBuf::from(new_path)
+}
+
+fn read_optional_file(path: &Path) -> crate::Result<Option<Vec<u8>>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    fs::read(path)
+        .map(Some)
+        .context(format!("Couldn't read the file: {}", path.to_str().unwrap_or("")))
+}
+
+fn collect_files(dir: &Path, rel_dir: &Path, rel_paths: &mut BTreeSet<PathBuf>) -> crate::Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir).context(format!("Failed to walk dir: {}", dir.to_str().unwrap_or("")))? {
+        let entry = entry.wrap()?;
+        let rel_path = rel_dir.join(entry.file_name());
+
+        if entry.file_type().wrap()?.is_dir() {
+            collect_files(entry.path().as_path(), rel_path.as_path(), rel_paths)?;
+        } else {
+            rel_paths.insert(rel_path);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_summary(summary: &UpdateSummary) {
+    println!();
+
+    for path in summary.updated.iter() {
+        println!("{} {}", "Updated".green(), path.to_str().unwrap_or(""));
+    }
+
+    for path in summary.merged.iter() {
+        println!("{} {}", "Merged".green(), path.to_str().unwrap_or(""));
+    }
+
+    for path in summary.removed.iter() {
+        println!("{} {}", "Removed".yellow(), path.to_str().unwrap_or(""));
+    }
+
+    for path in summary.kept.iter() {
+        println!(
+            "{} {} (removed from the template but modified in the project)",
+            "Kept".yellow(),
+            path.to_str().unwrap_or("")
+        );
+    }
+
+    for path in summary.skipped.iter() {
+        println!(
+            "{} {} (changed in the template but removed from the project)",
+            "Skipped".yellow(),
+            path.to_str().unwrap_or("")
+        );
+    }
+
+    for path in summary.conflicted.iter() {
+        println!("{} {}", "Conflict".red(), path.to_str().unwrap_or(""));
+    }
+
+    for path in summary.conflicted_binary.iter() {
+        println!(
+            "{} {} (binary, the template version is written to {})",
+            "Conflict".red(),
+            path.to_str().unwrap_or(""),
+            new_file_path(path).to_str().unwrap_or("")
+        );
+    }
+
+    let conflicts = summary.conflicted.len() + summary.conflicted_binary.len();
+    if conflicts == 0 {
+        println!("\nSuccess! The project is updated with the latest template\n");
+    } else {
+        println!(
+            "\n{} {} file(s) have conflicts, resolve the conflict markers or pick between the binary files and their {} \
+             versions before committing\n",
+            "Warning:".yellow(),
+            conflicts,
+            constants::UPDATE_NEW_FILE_EXTENSION
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_a_binary_file() {
+        let root = TempDir::new().unwrap();
+        let project_dir = root.path().join("app");
+        fs::create_dir_all(project_dir.as_path()).unwrap();
+
+        let base_path = root.path().join("logo.base.png");
+        let theirs_path = root.path().join("logo.theirs.png");
+        fs::write(base_path.as_path(), [0x89, 0x50, 0xff, 0x00]).unwrap();
+        fs::write(theirs_path.as_path(), [0x89, 0x50, 0xfe, 0x01]).unwrap();
+        fs::write(project_dir.join("logo.png"), [0x89, 0x50, 0xfd, 0x02]).unwrap();
+
+        let mut summary = UpdateSummary::default();
+        let mut transaction = Transaction::new(project_dir.as_path()).unwrap();
+        merge_a_single_file(
+            &mut transaction,
+            Path::new("logo.png"),
+            base_path.as_path(),
+            theirs_path.as_path(),
+            project_dir.join("logo.png").as_path(),
+            &mut summary,
+        )
+        .unwrap();
+        transaction.commit().unwrap();
+
+        assert_eq!(summary.conflicted_binary, vec![PathBuf::from("logo.png")]);
+        assert_eq!(
+            fs::read(project_dir.join("logo.png")).unwrap(),
+            vec![0x89, 0x50, 0xfd, 0x02]
+        );
+        assert_eq!(
+            fs::read(project_dir.join("logo.png.new")).unwrap(),
+            vec![0x89, 0x50, 0xfe, 0x01]
+        );
+    }
+}
@@ -8,13 +8,30 @@ The {} can be one of:
     - a template published on https://github.com/boilerplato: e.g. {}
     - a Github repo under an username or an organization: e.g. {}
     - a local template path relative to the current working directory: e.g. {}
-    - any git repo having boilerplato.yml or boilerplato.json file: e.g. {}\n\n\
+    - any git repo having boilerplato.yml or boilerplato.json file: e.g. {}
+    - a repo on GitLab, Bitbucket, sourcehut or a forge from the user config: e.g. {}
+    - a git repo over SSH or the git protocol: e.g. {}
+    - a .tar.gz, .tgz or .zip archive as a local path or an URL: e.g. {}
+
+A git branch, tag or commit can be pinned with a {} suffix or with {}: e.g. {}
+The highest template tag matching a semver range can be used with a {} suffix: e.g. {}
+A template in a subdirectory of a repo is given after the repo: e.g. {} or {}\n\n\
         ",
         "--template".cyan(),
         "react-nodejs-template".green(),
         "john/web-app-template".green(),
         "file:../my-custom-template".green(),
-        "https://github.com/foo/bar.git".green()
+        "https://github.com/foo/bar.git".green(),
+        "gitlab:group/web-app-template".green(),
+        "git@github.com:foo/bar.git".green(),
+        "https://example.com/web-app-template-1.0.0.tar.gz".green(),
+        "#<ref>".cyan(),
+        "--ref".cyan(),
+        "john/web-app-template#v2.1.0".green(),
+        "@<range>".cyan(),
+        "john/web-app-template@^2.0".green(),
+        "john/templates/web-app".green(),
+        "https://github.com/foo/bar.git//web-app".green()
     );
 }
 
@@ -23,10 +40,12 @@ lazy_static! {
         "\
 Examples:
     - create a react.js app: {}
-    - create a Rust CLI app: {}\n\n\
+    - create a Rust CLI app: {}
+    - create an app without prompts: {}\n\n\
         ",
         "$ boilerplato my-app --template react-nodejs-template".green(),
-        "$ boilerplato my-app --template rust-cli-template".green()
+        "$ boilerplato my-app --template rust-cli-template".green(),
+        "$ boilerplato my-app --template rust-cli-template --data answers.yml --yes".green()
     );
 }
 
@@ -34,11 +53,65 @@ lazy_static! {
     static ref SUB_COMMAND_SEARCH_EXAMPLES_HELP_TEXT: String = format!(
         "\
 Examples:
+    {}
+    {}
     {}
     {}
         ",
         "$ boilerplato search nodejs".green(),
-        "$ boilerplato search rust".green()
+        "$ boilerplato search rust --sort updated --topic cli --limit 10".green(),
+        "$ boilerplato search react --language typescript --format json".green(),
+        "$ boilerplato search rust --pick".green()
+    );
+}
+
+lazy_static! {
+    static ref SUB_COMMAND_INFO_EXAMPLES_HELP_TEXT: String = format!(
+        "\
+Examples:
+    {}
+    {}
+    {}
+        ",
+        "$ boilerplato info john/web-app-template".green(),
+        "$ boilerplato info john/web-app-template --ref v2.0.0".green(),
+        "$ boilerplato info file:./my-template --format json".green()
+    );
+}
+
+lazy_static! {
+    static ref SUB_COMMAND_UPDATE_EXAMPLES_HELP_TEXT: String = format!(
+        "\
+The project is updated with a three-way merge between the template version it was generated from,
+the latest template version and the project files, the colliding changes are written with conflict markers.
+
+Examples:
+    {}
+    {}
+    {}
+        ",
+        "$ boilerplato update".green(),
+        "$ boilerplato update my-app --set license=MIT".green(),
+        "$ boilerplato update my-app --ref v3.0.0".green()
+    );
+}
+
+lazy_static! {
+    static ref SUB_COMMAND_CACHE_EXAMPLES_HELP_TEXT: String = format!(
+        "\
+The fetched templates are cached in $XDG_CACHE_HOME/boilerplato, or in $BOILERPLATO_CACHE_DIR if it's set.
+The cached templates are updated before use and can be used without network access with --offline.
+
+Examples:
+    {}
+    {}
+    {}
+    {}
+        ",
+        "$ boilerplato cache list".green(),
+        "$ boilerplato cache update john/web-app-template".green(),
+        "$ boilerplato cache clear".green(),
+        "$ boilerplato my-app --template john/web-app-template --offline".green()
     );
 }
 
@@ -51,18 +124,57 @@ lazy_static! {
         format!("boilerplato search {} [OPTIONS]", "<search-text>".green());
 }
 
+lazy_static! {
+    static ref SUB_COMMAND_INFO_SHORT_USAGE_TEXT: String =
+        format!("boilerplato info {} [OPTIONS]", "<template>".green());
+}
+
+lazy_static! {
+    static ref SUB_COMMAND_UPDATE_SHORT_USAGE_TEXT: String =
+        format!("boilerplato update {} [OPTIONS]", "[project-directory]".green());
+}
+
+lazy_static! {
+    static ref SUB_COMMAND_CACHE_SHORT_USAGE_TEXT: String =
+        format!("boilerplato cache {} [OPTIONS]", "<list|clear|update>".green());
+}
+
 pub fn app_help_text() -> String {
     format!("{}{}", &*TEMPLATE_HELP_TEXT, &*EXAMPLES_HELP_TEXT)
 }
 
 pub fn app_short_usage_text() -> String {
-    format!("{}", &*APP_SHORT_USAGE_TEXT)
+    APP_SHORT_USAGE_TEXT.to_string()
 }
 
 pub fn sub_command_search_help_text() -> String {
-    format!("{}", &*SUB_COMMAND_SEARCH_EXAMPLES_HELP_TEXT)
+    SUB_COMMAND_SEARCH_EXAMPLES_HELP_TEXT.to_string()
 }
 
 pub fn sub_command_search_short_usage_text() -> String {
-    format!("{}", &*SUB_COMMAND_SEARCH_SHORT_USAGE_TEXT)
+    SUB_COMMAND_SEARCH_SHORT_USAGE_TEXT.to_string()
+}
+
+pub fn sub_command_info_help_text() -> String {
+    SUB_COMMAND_INFO_EXAMPLES_HELP_TEXT.to_string()
+}
+
+pub fn sub_command_info_short_usage_text() -> String {
+    SUB_COMMAND_INFO_SHORT_USAGE_TEXT.to_string()
+}
+
+pub fn sub_command_update_help_text() -> String {
+    SUB_COMMAND_UPDATE_EXAMPLES_HELP_TEXT.to_string()
+}
+
+pub fn sub_command_update_short_usage_text() -> String {
+    SUB_COMMAND_UPDATE_SHORT_USAGE_TEXT.to_string()
+}
+
+pub fn sub_command_cache_help_text() -> String {
+    SUB_COMMAND_CACHE_EXAMPLES_HELP_TEXT.to_string()
+}
+
+pub fn sub_command_cache_short_usage_text() -> String {
+    SUB_COMMAND_CACHE_SHORT_USAGE_TEXT.to_string()
 }
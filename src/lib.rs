@@ -1,11 +1,13 @@
 pub use self::error::{Error, ErrorExt, ResultExt};
 
+pub mod config;
 pub mod constants;
 pub mod data_prompts;
 mod error;
 pub mod generator;
 pub mod help;
 pub mod prelude;
+pub mod registry;
 pub mod search;
 pub mod template_engine;
 pub mod types;
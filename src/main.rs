@@ -1,9 +1,18 @@
 extern crate boilerplato;
+use boilerplato::config::UserConfig;
 use boilerplato::constants;
-use clap::{App, Arg, SubCommand};
+use boilerplato::data_prompts;
+use boilerplato::generator::{CachePolicy, GenerateOptions};
+use boilerplato::search::{SearchOptions, SearchSort};
+use boilerplato::types::{AnswersFile, ColorChoice, ConflictStrategy, OutputFormat};
+use boilerplato::utils::interrupt;
+use clap::{App, Arg, ArgMatches, SubCommand};
 use colored::*;
+use std::process;
 
 fn main() {
+    interrupt::install_handler();
+
     let matches = App::new(constants::APP_NAME)
         .version(constants::APP_VERSION)
         .version_short("v")
@@ -25,6 +34,48 @@ fn main() {
                 .takes_value(true)
                 .required(false),
         )
+        .arg(template_ref_arg())
+        .arg(offline_arg())
+        .args(&template_data_args())
+        .arg(
+            Arg::with_name("answersFrom")
+                .long("answers-from")
+                .value_name("answers-file")
+                .help("Replay the answers recorded in a previously generated project")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("dryRun")
+                .long("dry-run")
+                .help("Print the files that would be generated without writing anything")
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("onConflict")
+                .long("on-conflict")
+                .value_name("strategy")
+                .help("What to do when a generated file already exists in the project directory [default: backup]")
+                .takes_value(true)
+                .possible_values(&constants::CONFLICT_STRATEGIES)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("noGitInit")
+                .long("no-git-init")
+                .help("Don't initialize a git repo in the project directory")
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("color")
+                .long("color")
+                .value_name("when")
+                .help("When to colour the output [default: auto]")
+                .takes_value(true)
+                .possible_values(&constants::COLOR_CHOICES)
+                .global(true)
+                .required(false),
+        )
         .usage(boilerplato::help::app_short_usage_text().as_str())
         .after_help(boilerplato::help::app_help_text().as_str())
         .subcommand(
@@ -32,20 +83,144 @@ fn main() {
                 .version(constants::APP_VERSION)
                 .version_short("v")
                 .author(constants::APP_AUTHOR)
-                .about("Search templates published on https://github.com/boilerplato.")
+                .about("Search templates published by the configured GitHub orgs, https://github.com/boilerplato by default.")
                 .arg(Arg::with_name("query")
                     .help("The search text to query templates")
                     .value_name("search-text")
                     .index(1)
                     .required(false))
+                .arg(Arg::with_name("page")
+                    .long("page")
+                    .value_name("page")
+                    .help("The result page to show [default: 1]")
+                    .takes_value(true)
+                    .required(false))
+                .arg(Arg::with_name("limit")
+                    .long("limit")
+                    .value_name("limit")
+                    .help("The number of results per page, at most 100 [default: 30]")
+                    .takes_value(true)
+                    .required(false))
+                .arg(Arg::with_name("sort")
+                    .long("sort")
+                    .value_name("sort")
+                    .help("Sort the results by [default: stars]")
+                    .takes_value(true)
+                    .possible_values(&constants::SEARCH_SORTS)
+                    .required(false))
+                .arg(Arg::with_name("topic")
+                    .long("topic")
+                    .value_name("topic")
+                    .help("Only show the templates with the topic, can be used multiple times")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .required(false))
+                .arg(Arg::with_name("language")
+                    .long("language")
+                    .value_name("language")
+                    .help("Only show the templates in the language")
+                    .takes_value(true)
+                    .required(false))
+                .arg(output_format_arg())
+                .arg(Arg::with_name("refresh")
+                    .long("refresh")
+                    .help("Fetch the results again instead of using the cached ones")
+                    .required(false))
+                .arg(offline_arg())
+                .arg(Arg::with_name("pick")
+                    .long("pick")
+                    .help("Pick a template from the results and generate a project with it")
+                    .required(false))
                 .usage(boilerplato::help::sub_command_search_short_usage_text().as_str())
                 .after_help(boilerplato::help::sub_command_search_help_text().as_str())
         )
+        .subcommand(
+            SubCommand::with_name("info")
+                .version(constants::APP_VERSION)
+                .version_short("v")
+                .author(constants::APP_AUTHOR)
+                .about("Show the data, the conditional files and the post generate commands of a template without generating a project.")
+                .arg(Arg::with_name("template")
+                    .help("The template to inspect, in any form accepted by --template")
+                    .value_name("template")
+                    .index(1)
+                    .required(true))
+                .arg(template_ref_arg())
+                .arg(offline_arg())
+                .arg(output_format_arg())
+                .usage(boilerplato::help::sub_command_info_short_usage_text().as_str())
+                .after_help(boilerplato::help::sub_command_info_help_text().as_str())
+        )
+        .subcommand(
+            SubCommand::with_name("update")
+                .version(constants::APP_VERSION)
+                .version_short("v")
+                .author(constants::APP_AUTHOR)
+                .about("Update a generated project with the latest version of its template.")
+                .arg(Arg::with_name("projectDirectory")
+                    .help("The project directory generated by boilerplato, defaults to the current directory")
+                    .value_name("project-directory")
+                    .index(1)
+                    .required(false))
+                .arg(template_ref_arg())
+                .arg(offline_arg())
+                .args(&template_data_args())
+                .usage(boilerplato::help::sub_command_update_short_usage_text().as_str())
+                .after_help(boilerplato::help::sub_command_update_help_text().as_str())
+        )
+        .subcommand(
+            SubCommand::with_name("cache")
+                .version(constants::APP_VERSION)
+                .version_short("v")
+                .author(constants::APP_AUTHOR)
+                .about("Manage the locally cached templates.")
+                .subcommand(SubCommand::with_name("list").about("List the cached templates"))
+                .subcommand(
+                    SubCommand::with_name("clear")
+                        .about("Remove all the cached templates or the given one")
+                        .arg(cached_template_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("update")
+                        .about("Fetch the latest version of all the cached templates or the given one")
+                        .arg(cached_template_arg()),
+                )
+                .usage(boilerplato::help::sub_command_cache_short_usage_text().as_str())
+                .after_help(boilerplato::help::sub_command_cache_help_text().as_str())
+        )
         .get_matches();
 
+    let user_config = match UserConfig::load() {
+        Ok(user_config) => user_config,
+        Err(err) => return eprintln!("{} {}", "error:".red(), err),
+    };
+
+    let active_matches = matches.subcommand().1.unwrap_or(&matches);
+    active_matches
+        .value_of("color")
+        .and_then(ColorChoice::parse)
+        .or(user_config.color)
+        .unwrap_or_default()
+        .apply();
+
     if let Some(project_directory) = matches.value_of("projectDirectory") {
-        if let Some(template) = matches.value_of("template") {
-            if let Err(err) = boilerplato::generator::gen_source_code_from_template(project_directory, template) {
+        let answers = match matches.value_of("answersFrom").map(AnswersFile::load).transpose() {
+            Ok(answers) => answers,
+            Err(err) => return eprintln!("{} {}", "error:".red(), err),
+        };
+
+        let template = matches
+            .value_of("template")
+            .map(|template| template.to_owned())
+            .or_else(|| answers.as_ref().map(|answers| answers.template.source.clone()));
+
+        if let Some(template) = template {
+            let result = gen_options(&matches, answers.as_ref(), &user_config).and_then(|options| {
+                boilerplato::generator::gen_source_code_from_template(project_directory, template, &options)
+            });
+
+            if let Err(err) = result {
                 eprintln!("{} {}", "error:".red(), err)
             }
         } else {
@@ -57,8 +232,17 @@ fn main() {
             )
         }
     } else if let Some(search_matches) = matches.subcommand_matches("search") {
-        if let Some(search_query) = search_matches.value_of("query") {
-            if let Err(err) = boilerplato::search::search_templates_from_registry(search_query) {
+        if search_matches.is_present("pick") {
+            let result = pick_and_generate(search_matches.value_of("query"), search_matches, &user_config);
+
+            if let Err(err) = result {
+                eprintln!("{} {}", "error:".red(), err)
+            }
+        } else if let Some(search_query) = search_matches.value_of("query") {
+            let result = search_options(search_matches, &user_config)
+                .and_then(|options| boilerplato::search::search_templates_from_registry(search_query, &options));
+
+            if let Err(err) = result {
                 eprintln!("{} {}", "error:".red(), err)
             }
         } else {
@@ -69,6 +253,55 @@ fn main() {
                 "boilerplato search --help".green()
             )
         }
+    } else if let Some(info_matches) = matches.subcommand_matches("info") {
+        let options = GenerateOptions {
+            template_ref: info_matches.value_of("ref").map(|git_ref| git_ref.to_owned()),
+            offline: info_matches.is_present("offline"),
+            ..GenerateOptions::default()
+        };
+        let format = info_matches
+            .value_of("format")
+            .and_then(OutputFormat::parse)
+            .unwrap_or_default();
+
+        let template = info_matches.value_of("template").unwrap_or("");
+        if let Err(err) = boilerplato::generator::show_template_info(template, &options, format) {
+            eprintln!("{} {}", "error:".red(), err)
+        }
+    } else if let Some(update_matches) = matches.subcommand_matches("update") {
+        let project_directory = update_matches.value_of("projectDirectory").unwrap_or(".");
+
+        let result = gen_options(update_matches, None, &user_config)
+            .and_then(|options| boilerplato::generator::update_project_from_template(project_directory, &options));
+
+        if let Err(err) = result {
+            eprintln!("{} {}", "error:".red(), err)
+        }
+    } else if let Some(cache_matches) = matches.subcommand_matches("cache") {
+        let result = match cache_matches.subcommand() {
+            ("list", _) => boilerplato::generator::list_cached_templates(),
+            ("clear", Some(clear_matches)) => {
+                boilerplato::generator::clear_cached_templates(clear_matches.value_of("template"))
+            }
+            ("update", Some(update_matches)) => {
+                boilerplato::generator::update_cached_templates(update_matches.value_of("template"))
+            }
+            _ => Err(boilerplato::Error::new(format!(
+                "The cache command is required: list, clear or update\n\nFor more information try {}",
+                "boilerplato cache --help".green()
+            ))),
+        };
+
+        if let Err(err) = result {
+            eprintln!("{} {}", "error:".red(), err)
+        }
+    } else if matches.subcommand_name().is_none()
+        && matches.value_of("template").is_none()
+        && data_prompts::is_interactive()
+    {
+        if let Err(err) = pick_and_generate(None, &matches, &user_config) {
+            eprintln!("{} {}", "error:".red(), err)
+        }
     } else {
         eprintln!(
             "{} The required arguments were not provided.\n\nFor more information try {}",
@@ -76,4 +309,169 @@ fn main() {
             "boilerplato --help".green()
         )
     }
+
+    // A Ctrl-C inside a critical section stops the command with an error, the exit code still tells it apart.
+    if interrupt::is_interrupted() {
+        process::exit(interrupt::INTERRUPTED_EXIT_CODE);
+    }
+}
+
+fn template_ref_arg() -> Arg<'static, 'static> {
+    Arg::with_name("ref")
+        .long("ref")
+        .value_name("git-ref")
+        .help("The git branch, tag or commit of the template to use, defaults to the default branch")
+        .takes_value(true)
+        .required(false)
+}
+
+fn offline_arg() -> Arg<'static, 'static> {
+    Arg::with_name("offline")
+        .long("offline")
+        .help("Use only the cached templates without accessing the network")
+        .required(false)
+}
+
+fn output_format_arg() -> Arg<'static, 'static> {
+    Arg::with_name("format")
+        .long("format")
+        .value_name("format")
+        .help("The output format [default: text]")
+        .takes_value(true)
+        .possible_values(&constants::OUTPUT_FORMATS)
+        .required(false)
+}
+
+fn cached_template_arg() -> Arg<'static, 'static> {
+    Arg::with_name("template")
+        .help("The cached template e.g. john/web-app-template or john/web-app-template#v1.0.0")
+        .value_name("template")
+        .index(1)
+        .required(false)
+}
+
+fn template_data_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("data")
+            .long("data")
+            .value_name("answers-file")
+            .help("Read the template data from a JSON or YAML file")
+            .takes_value(true)
+            .required(false),
+        Arg::with_name("set")
+            .long("set")
+            .value_name("name=value")
+            .help("Set a template data value, can be used multiple times")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .required(false),
+        Arg::with_name("defaults")
+            .short("y")
+            .long("defaults")
+            .alias("yes")
+            .help("Use the default values for the optional template data without prompting")
+            .required(false),
+    ]
+}
+
+fn gen_options(
+    matches: &ArgMatches,
+    answers: Option<&AnswersFile>,
+    user_config: &UserConfig,
+) -> boilerplato::Result<GenerateOptions> {
+    let mut options = GenerateOptions {
+        default_data: user_config.answers.clone(),
+        ..GenerateOptions::default()
+    };
+
+    if let Some(answers) = answers {
+        options.data.extend(answers.data.clone());
+    }
+
+    if let Some(data_file) = matches.value_of("data") {
+        options.data.extend(data_prompts::load_data_file(data_file)?);
+    }
+
+    for assignment in matches.values_of("set").into_iter().flatten() {
+        let (name, value) = data_prompts::parse_data_assignment(assignment)?;
+        options.data.insert(name, value);
+    }
+
+    options.template_ref = matches.value_of("ref").map(|r| r.to_owned());
+    options.use_defaults = matches.is_present("defaults");
+    options.dry_run = matches.is_present("dryRun");
+    options.offline = matches.is_present("offline");
+    options.on_conflict = matches
+        .value_of("onConflict")
+        .and_then(ConflictStrategy::parse)
+        .or(user_config.on_conflict);
+    options.skip_git_init = matches.is_present("noGitInit") || user_config.git_init == Some(false);
+
+    Ok(options)
+}
+
+/// Searches the templates, lets the user pick one and generates the project with it.
+fn pick_and_generate(
+    search_text: Option<&str>,
+    matches: &ArgMatches,
+    user_config: &UserConfig,
+) -> boilerplato::Result<()> {
+    let search_options = search_options(matches, user_config)?;
+
+    if let Some((template, project_directory)) = boilerplato::search::pick_template(search_text, &search_options)? {
+        let options = gen_options(matches, None, user_config)?;
+        println!();
+        boilerplato::generator::gen_source_code_from_template(project_directory, template, &options)?;
+    }
+
+    Ok(())
+}
+
+fn search_options(matches: &ArgMatches, user_config: &UserConfig) -> boilerplato::Result<SearchOptions> {
+    let mut options = SearchOptions::default();
+
+    if let Some(page) = matches.value_of("page") {
+        options.page =
+            page.parse::<usize>().ok().filter(|page| *page > 0).ok_or_else(|| {
+                boilerplato::Error::new(format!("Invalid page: {}, it must be a positive number", page))
+            })?;
+    }
+
+    if let Some(limit) = matches.value_of("limit") {
+        options.limit = limit
+            .parse::<usize>()
+            .ok()
+            .filter(|limit| *limit > 0 && *limit <= constants::SEARCH_MAX_LIMIT)
+            .ok_or_else(|| {
+                boilerplato::Error::new(format!(
+                    "Invalid limit: {}, it must be between 1 and {}",
+                    limit,
+                    constants::SEARCH_MAX_LIMIT
+                ))
+            })?;
+    }
+
+    if let Some(sort) = matches.value_of("sort").and_then(SearchSort::parse) {
+        options.sort = sort;
+    }
+
+    options.topics = matches
+        .values_of("topic")
+        .into_iter()
+        .flatten()
+        .map(|topic| topic.to_owned())
+        .collect();
+    options.language = matches.value_of("language").map(|language| language.to_owned());
+    options.format = matches
+        .value_of("format")
+        .and_then(OutputFormat::parse)
+        .unwrap_or_default();
+    options.cache_policy = Some(CachePolicy {
+        ttl: user_config.search_cache_ttl(),
+        refresh: matches.is_present("refresh"),
+        offline: matches.is_present("offline"),
+    });
+
+    Ok(options)
 }
@@ -0,0 +1,230 @@
+use crate::constants;
+use crate::generator::{CachePolicy, ResponseCache};
+use crate::prelude::*;
+use crate::utils;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use url::Url;
+
+/// A static template catalogue in JSON or YAML, served over HTTP or read from a local file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryIndex {
+    #[serde(default)]
+    pub templates: Vec<RegistryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryEntry {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The template specifier or URL e.g. `corp:platform/api-template`.
+    pub source: String,
+    /// The latest released version, the bare names resolved through the registry are fetched at it.
+    pub latest_version: Option<String>,
+    #[serde(default)]
+    pub maintainers: Vec<String>,
+}
+
+impl RegistryIndex {
+    /// Parses a JSON or a YAML index, as YAML is a superset of JSON.
+    pub fn parse(text: &str) -> crate::Result<RegistryIndex> {
+        if text.trim().is_empty() {
+            return Ok(RegistryIndex::default());
+        }
+
+        serde_yaml::from_str::<RegistryIndex>(text).wrap()
+    }
+
+    /// Loads the index from an HTTP(S) URL, a `file:` path or URL, or a plain path. The remote indexes are cached
+    /// if a cache policy is given.
+    pub fn load(location: &str, cache_policy: Option<&CachePolicy>) -> crate::Result<RegistryIndex> {
+        let display_location = utils::redact_credentials(location);
+
+        let text = if is_remote(location) {
+            match cache_policy {
+                Some(cache_policy) => {
+                    ResponseCache::open().fetch(location, location, cache_policy, || download_index(location))?
+                }
+                None => download_index(location)?,
+            }
+        } else {
+            let path = local_path(location)?;
+            fs::read_to_string(path.as_path())
+                .context(format!("Couldn't read the template registry: {}", display_location))?
+        };
+
+        RegistryIndex::parse(text.as_str()).context(format!("Invalid template registry: {}", display_location))
+    }
+
+    /// The templates matching all the words of the search text in their name, description or tags.
+    pub fn search(&self, search_text: &str) -> Vec<&RegistryEntry> {
+        let words = search_text
+            .split_whitespace()
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<String>>();
+
+        self.templates
+            .iter()
+            .filter(|entry| {
+                let name = entry.name.to_lowercase();
+                let description = entry.description.to_lowercase();
+
+                words.iter().all(|word| {
+                    name.contains(word.as_str())
+                        || description.contains(word.as_str())
+                        || entry.tags.iter().any(|tag| tag.to_lowercase() == *word)
+                })
+            })
+            .collect()
+    }
+
+    pub fn find(&self, name: &str) -> Option<&RegistryEntry> {
+        self.templates.iter().find(|entry| entry.name == name)
+    }
+}
+
+fn is_remote(location: &str) -> bool {
+    location.starts_with(constants::TEMPLATE_PREFIX_HTTP) || location.starts_with(constants::TEMPLATE_PREFIX_HTTPS)
+}
+
+fn local_path(location: &str) -> crate::Result<PathBuf> {
+    if location.starts_with("file://") {
+        return Url::parse(location)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| crate::Error::new(format!("Invalid template registry URL: {}", location)));
+    }
+
+    Ok(PathBuf::from(
+        location
+            .strip_prefix(constants::TEMPLATE_PREFIX_FILE)
+            .unwrap_or(location),
+    ))
+}
+
+fn download_index(url: &str) -> crate::Result<String> {
+    // The URL can have the credentials in it, the reqwest errors include the URL too.
+    let display_url = utils::redact_credentials(url);
+    let download_err = |err: reqwest::Error| {
+        crate::Error::new(format!(
+            "Couldn't download the template registry: {}: {}",
+            display_url,
+            utils::redact_credentials(err.to_string().as_str())
+        ))
+    };
+
+    let resp = Client::new()
+        .get(url)
+        .header(
+            "User-Agent",
+            format!("{} v{}", constants::APP_NAME, constants::APP_VERSION),
+        )
+        .send()
+        .map_err(download_err)?;
+
+    if !resp.status().is_success() {
+        return Err(crate::Error::new(format!(
+            "Couldn't download the template registry: {}: the server responded with {}",
+            display_url,
+            resp.status()
+        )));
+    }
+
+    resp.text().map_err(download_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    const INDEX_YAML: &str = "\
+templates:
+  - name: api
+    description: A REST API server in Rust
+    tags: [rust, api]
+    source: corp:platform/api-template
+    latestVersion: 3.1.0
+    maintainers: [platform@corp.example]
+  - name: web
+    description: A React web app
+    tags: [react]
+    source: https://git.corp.example/web/web-template.git
+";
+
+    #[test]
+    fn test_registry_index_search() {
+        let index = RegistryIndex::parse(INDEX_YAML).unwrap();
+
+        let names = |text: &str| {
+            index
+                .search(text)
+                .iter()
+                .map(|entry| entry.name.as_str())
+                .collect::<Vec<&str>>()
+        };
+
+        assert_eq!(names("rust"), vec!["api"]);
+        assert_eq!(names("REACT app"), vec!["web"]);
+        assert_eq!(names("a"), vec!["api", "web"]);
+        assert!(names("golang").is_empty());
+
+        let api = index.find("api").unwrap();
+        assert_eq!(api.latest_version.as_deref(), Some("3.1.0"));
+        assert_eq!(api.maintainers, vec!["platform@corp.example"]);
+        assert!(index.find("rust").is_none());
+
+        let json_index =
+            RegistryIndex::parse(r#"{"templates": [{"name": "cli", "source": "john/cli-template"}]}"#).unwrap();
+        assert_eq!(json_index.find("cli").unwrap().source, "john/cli-template");
+    }
+
+    #[test]
+    fn test_registry_index_load() {
+        let dir = utils::TempDir::new().unwrap();
+        let index_path = dir.path().join("index.yml");
+        fs::write(index_path.as_path(), INDEX_YAML).unwrap();
+
+        let index_url = Url::from_file_path(index_path.as_path()).unwrap();
+        for location in [index_path.to_str().unwrap().to_owned(), index_url.to_string()].iter() {
+            assert_eq!(RegistryIndex::load(location, None).unwrap().templates.len(), 2);
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            for status in ["200 OK", "404 Not Found"].iter() {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0; 1024];
+                let _ = stream.read(&mut buf).unwrap();
+                let body = if status.starts_with("200") { INDEX_YAML } else { "" };
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        let url = format!("http://{}/index.yml", addr);
+        assert_eq!(RegistryIndex::load(url.as_str(), None).unwrap().templates.len(), 2);
+        assert!(RegistryIndex::load(url.as_str(), None)
+            .unwrap_err()
+            .to_string()
+            .contains("404"));
+
+        server.join().unwrap();
+    }
+}
@@ -1,13 +1,78 @@
+pub use self::options::{SearchOptions, SearchSort};
+use crate::config::UserConfig;
 use crate::constants;
+use crate::data_prompts;
+use crate::generator::ResponseCache;
 use crate::prelude::*;
+use crate::registry::RegistryIndex;
+use crate::types::{OutputFormat, TemplateData, TemplateDataType};
+use crate::utils;
 use colored::*;
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, Response};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
 
-pub fn search_templates_from_registry(search_text: &str) -> crate::Result<()> {
-    let results = search_templates_from_github(search_text)?;
+mod options;
 
-    if results.len() == 0 {
+/// A template found in the GitHub orgs or in a registry.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub name: String,
+    pub description: String,
+    /// The template specifier to generate a project with.
+    pub template: String,
+    /// The repo URL of the GitHub templates, the template source of the registry templates.
+    pub source: String,
+    /// The GitHub topics or the registry tags.
+    pub tags: Vec<String>,
+    pub stars: Option<u64>,
+    pub updated_at: Option<String>,
+    pub default_branch: Option<String>,
+    pub latest_version: Option<String>,
+    /// The registry listing the template, none for the GitHub templates.
+    pub registry: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GithubSearchResponse {
+    #[serde(default)]
+    items: Vec<GithubRepo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GithubRepo {
+    name: String,
+    owner: GithubRepoOwner,
+    description: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    stargazers_count: u64,
+    updated_at: Option<String>,
+    default_branch: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GithubRepoOwner {
+    login: String,
+}
+
+pub fn search_templates_from_registry(search_text: &str, options: &SearchOptions) -> crate::Result<()> {
+    let results = search_templates(search_text, options)?;
+
+    if options.format == OutputFormat::Json {
+        let json = serde_json::to_string_pretty(&results).context("Couldn't serialize the search results")?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    if results.is_empty() {
         println!();
         println!("   {}", "No templates found!".red());
         println!();
@@ -15,55 +80,630 @@ pub fn search_templates_from_registry(search_text: &str) -> crate::Result<()> {
     }
 
     println!();
-    for (name, desc) in results {
+    for result in results {
         println!(
             "   {} {}",
-            name.as_str(),
-            format!("$ {} my-app -t {}", constants::APP_NAME, name.as_str())
+            result.name.as_str(),
+            format!("$ {} my-app -t {}", constants::APP_NAME, result.template.as_str())
                 .as_str()
                 .cyan()
         );
-        println!("   {}", desc.as_str().bright_black());
+        print_result_details(&result);
         println!();
     }
 
     Ok(())
 }
 
-fn search_templates_from_github(search_text: &str) -> crate::Result<Vec<(String, String)>> {
-    let url = format!(
-        "{}?q={}+in:name,description+org:{}&sort=stars&order=desc",
-        constants::SEARCH_REPO_GITHUB_API_ENDPOINT,
-        search_text,
-        constants::BOILERPLATO_GITHUB_HANDLE
-    );
+/// Lists the found templates with numbers and asks for a template and the project directory, the search text is
+/// asked too if it's not given. Returns the template specifier and the project directory, or none if no template
+/// is found.
+pub fn pick_template(search_text: Option<&str>, options: &SearchOptions) -> crate::Result<Option<(String, String)>> {
+    if !data_prompts::is_interactive() {
+        return Err(crate::Error::new("The stdin is not a terminal to pick a template"));
+    }
+
+    let search_text = match search_text {
+        Some(search_text) => search_text.to_owned(),
+        None => {
+            let answer = data_prompts::ask_a_single_data(&TemplateData {
+                name: "search text".to_owned(),
+                data_type: TemplateDataType::String,
+                values: None,
+                message: "Search templates, leave empty to list all: ".to_owned(),
+                required: false,
+                default_value: None,
+            })?;
+            answer.as_str().unwrap_or("").to_owned()
+        }
+    };
+
+    let results = search_templates(search_text.as_str(), options)?;
+
+    println!();
+    if results.is_empty() {
+        println!("   {}", "No templates found!".red());
+        println!();
+        return Ok(None);
+    }
+
+    for (idx, result) in results.iter().enumerate() {
+        println!("   {} {}", format!("{:>2})", idx + 1).cyan(), result.name.as_str());
+        print_result_details(result);
+        println!();
+    }
+
+    let picked = data_prompts::ask_a_single_data(&TemplateData {
+        name: "template".to_owned(),
+        data_type: TemplateDataType::Number,
+        values: Some((1..=results.len()).map(Value::from).collect()),
+        message: format!("Pick a template (1-{}): ", results.len()),
+        required: true,
+        default_value: None,
+    })?;
+    let picked = picked_result(&results, &picked)?;
+
+    let project_dir = data_prompts::ask_a_single_data(&TemplateData {
+        name: "project directory".to_owned(),
+        data_type: TemplateDataType::String,
+        values: None,
+        message: "Enter the project directory: ".to_owned(),
+        required: false,
+        default_value: Some(Value::String(picked.name.clone())),
+    })?;
+
+    Ok(Some(picked_template(picked, &project_dir)))
+}
+
+/// Finds the result of the 1-based number picked from the listed results.
+fn picked_result<'a>(results: &'a [SearchResult], picked: &Value) -> crate::Result<&'a SearchResult> {
+    // The prompted numbers are floats, the whole ones are taken as the result numbers.
+    let number = picked.as_u64().or_else(|| {
+        picked
+            .as_f64()
+            .filter(|number| number.fract() == 0.0 && *number >= 0.0)
+            .map(|number| number as u64)
+    });
+
+    number
+        .and_then(|number| number.checked_sub(1))
+        .and_then(|idx| results.get(idx as usize))
+        .ok_or_else(|| crate::Error::new(format!("Invalid template number: {}", picked)))
+}
+
+/// The template source and the project directory to generate the picked result into, the project directory
+/// defaults to the result name. The full source is used as a bare name could resolve to another template.
+fn picked_template(picked: &SearchResult, project_dir: &Value) -> (String, String) {
+    let project_dir = project_dir
+        .as_str()
+        .filter(|dir| !dir.trim().is_empty())
+        .unwrap_or(picked.name.as_str())
+        .to_owned();
+
+    (picked.source.clone(), project_dir)
+}
+
+/// Searches the GitHub orgs and the registries, the sources which can't be searched are reported as warnings.
+pub fn search_templates(search_text: &str, options: &SearchOptions) -> crate::Result<Vec<SearchResult>> {
+    let user_config = UserConfig::load()?;
+    let trusted_config = UserConfig::load_trusted()?;
+
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
 
-    let resp = Client::new()
+    let mut indexes = Vec::new();
+    for location in user_config.registries.iter() {
+        let location = user_config.rewrite_url(location);
+        match RegistryIndex::load(location.as_str(), options.cache_policy.as_ref()) {
+            Ok(index) => indexes.push((location, index)),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    // A bare name resolves through the registries first, so it can stand for a GitHub repo only if no registry has it.
+    let is_registry_name = |name: &str| indexes.iter().any(|(_, index)| index.find(name).is_some());
+
+    match search_templates_from_github(&user_config, &trusted_config, search_text, options, &is_registry_name) {
+        Ok(github_results) => results.extend(github_results),
+        Err(err) => errors.insert(0, err),
+    }
+
+    for (location, index) in indexes.iter() {
+        results.extend(search_templates_from_index(
+            location.as_str(),
+            index,
+            search_text,
+            options,
+        ));
+    }
+
+    // The search fails only if none of the GitHub orgs and the registries could be searched.
+    if errors.len() == user_config.registries.len() + 1 {
+        return Err(errors.remove(0));
+    }
+
+    // The warnings go to stderr to keep the JSON output parsable.
+    for err in errors {
+        eprintln!("{} {}", "Warning:".yellow(), err);
+    }
+
+    if options.sort == SearchSort::Name {
+        results.sort_by_key(|result| result.name.to_lowercase());
+    }
+
+    Ok(results)
+}
+
+fn print_result_details(result: &SearchResult) {
+    if !result.description.is_empty() {
+        println!("   {}", result.description.as_str().bright_black());
+    }
+
+    let details = result
+        .stars
+        .iter()
+        .map(|stars| format!("★ {}", stars))
+        .chain(
+            result
+                .updated_at
+                .iter()
+                .map(|updated_at| format!("updated {}", updated_at.split('T').next().unwrap_or(""))),
+        )
+        .chain(result.default_branch.iter().map(|branch| format!("branch {}", branch)))
+        .chain(
+            result
+                .latest_version
+                .iter()
+                .map(|version| format!("v{}", version.trim_start_matches('v'))),
+        )
+        .chain(result.tags.iter().map(|tag| format!("#{}", tag)))
+        .collect::<Vec<String>>();
+    if !details.is_empty() {
+        println!("   {}", details.join("  ").bright_black());
+    }
+}
+
+fn search_templates_from_index(
+    location: &str,
+    index: &RegistryIndex,
+    search_text: &str,
+    options: &SearchOptions,
+) -> Vec<SearchResult> {
+    let has_tag = |tags: &[String], wanted: &str| tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted));
+
+    index
+        .search(search_text)
+        .into_iter()
+        .filter(|entry| options.topics.iter().all(|topic| has_tag(&entry.tags, topic)))
+        .filter(|entry| {
+            options
+                .language
+                .as_ref()
+                .map(|language| has_tag(&entry.tags, language))
+                .unwrap_or(true)
+        })
+        .skip(options.page.saturating_sub(1) * options.limit)
+        .take(options.limit)
+        .map(|entry| SearchResult {
+            name: entry.name.clone(),
+            description: entry.description.clone(),
+            template: entry.name.clone(),
+            source: entry.source.clone(),
+            tags: entry.tags.clone(),
+            latest_version: entry.latest_version.clone(),
+            registry: Some(utils::redact_credentials(location)),
+            ..SearchResult::default()
+        })
+        .collect()
+}
+
+/// Searches the GitHub orgs, the trusted config is the one without the project's `.boilerplatorc` and decides
+/// whether the env tokens can be sent to the API endpoint. The repos of the first org are given as bare names
+/// unless a registry has the name.
+fn search_templates_from_github(
+    user_config: &UserConfig,
+    trusted_config: &UserConfig,
+    search_text: &str,
+    options: &SearchOptions,
+    is_registry_name: &dyn Fn(&str) -> bool,
+) -> crate::Result<Vec<SearchResult>> {
+    let endpoint = github_search_endpoint(user_config);
+    let orgs = user_config.orgs();
+    let url = github_search_url(endpoint.as_str(), search_text, &orgs, options)?;
+
+    let mut req = Client::new()
         .get(url.as_str())
         .header(
             "User-Agent",
             format!("{} v{}", constants::APP_NAME, constants::APP_VERSION),
         )
-        .send()
-        .context("Couldn't connect with Github API server to fetch templates list")?;
+        .header("Accept", "application/vnd.github+json");
+
+    let token = github_token(user_config, trusted_config, endpoint.as_str());
+    if let Some(ref token) = token {
+        req = req.header("Authorization", format!("token {}", token));
+    }
+
+    let fetch = || {
+        let resp = req
+            .send()
+            .context("Couldn't connect with Github API server to fetch templates list")?;
 
-    let data = resp.json::<Value>().context("Couldn't parse the Github API response")?;
+        check_github_response(resp, token.is_some())?
+            .text()
+            .context("Couldn't read the Github API response")
+    };
+
+    // The authenticated searches can find the private repos too.
+    let body = match options.cache_policy {
+        Some(ref cache_policy) => {
+            let cache_key = format!("{}#{}", url, utils::or(token.is_some(), "authenticated", "anonymous"));
+            ResponseCache::open().fetch(cache_key.as_str(), url.as_str(), cache_policy, fetch)?
+        }
+        None => fetch()?,
+    };
+
+    let data = serde_json::from_str::<GithubSearchResponse>(body.as_str())
+        .context("Couldn't parse the Github API response")?;
 
     Ok(data
-        .get("items")
-        .and_then(|val| val.as_array())
-        .iter()
-        .map(|val| val.iter())
-        .flatten()
-        .filter_map(|val: &Value| {
-            val.get("name").and_then(|name| {
-                name.as_str().and_then(|name| {
-                    val.get("description")
-                        .and_then(|desc| desc.as_str())
-                        .map(|desc| (name, desc))
-                })
-            })
+        .items
+        .into_iter()
+        .map(|repo| SearchResult {
+            // A bare name resolves to the first org having the template.
+            template: if Some(&repo.owner.login.as_str()) == orgs.first() && !is_registry_name(repo.name.as_str()) {
+                repo.name.clone()
+            } else {
+                format!("{}/{}", repo.owner.login, repo.name)
+            },
+            name: repo.name,
+            description: repo.description.unwrap_or_default(),
+            source: repo.html_url,
+            tags: repo.topics,
+            stars: Some(repo.stargazers_count),
+            updated_at: repo.updated_at,
+            default_branch: repo.default_branch,
+            ..SearchResult::default()
         })
-        .map(|(name, desc)| (name.to_string(), desc.to_string()))
         .collect())
 }
+
+/// The GitHub search API endpoint with the URL rewrites applied.
+fn github_search_endpoint(user_config: &UserConfig) -> String {
+    let endpoint = format!(
+        "{}/{}",
+        user_config.github_api_url().trim_end_matches('/'),
+        constants::SEARCH_REPO_GITHUB_API_PATH
+    );
+
+    user_config.rewrite_url(endpoint.as_str())
+}
+
+/// Finds the GitHub API token of the endpoint host, the host credentials in the user config take precedence over
+/// `$GITHUB_TOKEN`, and then over `$BOILERPLATO_GIT_TOKEN`. The credentials of `github.com` apply to `api.github.com`
+/// too. The env tokens are sent only to `api.github.com` and to the API endpoint of the trusted config, as the
+/// `.boilerplatorc` of a project can point the search anywhere.
+fn github_token(user_config: &UserConfig, trusted_config: &UserConfig, endpoint: &str) -> Option<String> {
+    let host = Url::parse(endpoint)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_owned()));
+
+    let config_token = host.as_ref().and_then(|host| {
+        let hosts = [Some(host.as_str()), host.strip_prefix("api.")];
+        hosts
+            .iter()
+            .flatten()
+            .filter_map(|host| user_config.host_credentials(host))
+            .find_map(|creds| creds.token())
+    });
+
+    let trusted_endpoint = endpoint.starts_with(format!("{}/", constants::GITHUB_API_URL).as_str())
+        || endpoint == github_search_endpoint(trusted_config);
+
+    config_token.or_else(|| {
+        if !trusted_endpoint {
+            return None;
+        }
+
+        [constants::GITHUB_TOKEN_ENV_VAR, constants::GIT_TOKEN_ENV_VAR]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|token| !token.trim().is_empty())
+    })
+}
+
+/// Turns the non-2xx responses into errors, the rate limit errors tell when the limit resets.
+fn check_github_response(resp: Response, authenticated: bool) -> crate::Result<Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+
+    let header = |name: &str| {
+        resp.headers()
+            .get(name)
+            .and_then(|val| val.to_str().ok())
+            .map(|val| val.trim().to_owned())
+    };
+    let remaining = header("x-ratelimit-remaining");
+    let reset = header("x-ratelimit-reset").and_then(|reset| reset.parse::<u64>().ok());
+    let retry_after = header("retry-after").and_then(|secs| secs.parse::<u64>().ok());
+
+    let message = resp.json::<Value>().ok().and_then(|body| {
+        body.get("message")
+            .and_then(|msg| msg.as_str())
+            .map(|msg| msg.to_owned())
+    });
+
+    let rate_limited = (status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS)
+        && (remaining.as_deref() == Some("0") || retry_after.is_some());
+
+    if rate_limited {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let reset_at = match (retry_after, reset) {
+            (Some(secs), _) => format!("retry after {} second(s)", secs),
+            (None, Some(reset)) => format!(
+                "it resets at {} (in {} minute(s))",
+                utils::format_utc_time(reset),
+                reset.saturating_sub(now).div_ceil(60)
+            ),
+            (None, None) => "try again later".to_owned(),
+        };
+
+        let hint = if authenticated {
+            String::new()
+        } else {
+            format!(
+                ", provide a token through ${} to raise the limit",
+                constants::GITHUB_TOKEN_ENV_VAR
+            )
+        };
+
+        return Err(crate::Error::new(format!(
+            "The Github API rate limit is exceeded, {}{}",
+            reset_at, hint
+        )));
+    }
+
+    Err(crate::Error::new(format!(
+        "The Github API responded with {}{}",
+        status,
+        message.map(|msg| format!(": {}", msg)).unwrap_or_default()
+    )))
+}
+
+/// Builds the GitHub search URL, the sort by name is done locally as GitHub doesn't support it.
+fn github_search_url(endpoint: &str, search_text: &str, orgs: &[&str], options: &SearchOptions) -> crate::Result<Url> {
+    let mut query = vec![search_text.trim().to_owned(), "in:name,description".to_owned()];
+    query.retain(|part| !part.is_empty());
+    query.extend(orgs.iter().map(|org| format!("org:{}", org)));
+    query.extend(options.topics.iter().map(|topic| format!("topic:{}", topic)));
+    query.extend(options.language.iter().map(|language| format!("language:{}", language)));
+
+    let mut url = Url::parse(endpoint).context("Invalid Github search API endpoint")?;
+    {
+        let mut pairs = url.query_pairs_mut();
+        pairs.append_pair("q", query.join(" ").as_str());
+
+        match options.sort {
+            SearchSort::Stars => {
+                pairs.append_pair("sort", "stars").append_pair("order", "desc");
+            }
+            SearchSort::Updated => {
+                pairs.append_pair("sort", "updated").append_pair("order", "desc");
+            }
+            SearchSort::Name => {}
+        }
+
+        pairs
+            .append_pair("per_page", options.limit.to_string().as_str())
+            .append_pair("page", options.page.to_string().as_str());
+    }
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Serves the raw responses in order on a local port, and returns the base URL and the received requests.
+    fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/api/v3", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            responses
+                .iter()
+                .map(|resp| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut buf = [0; 4096];
+                    let len = stream.read(&mut buf).unwrap();
+                    stream.write_all(resp.as_bytes()).unwrap();
+                    String::from_utf8_lossy(&buf[..len]).to_lowercase()
+                })
+                .collect()
+        });
+
+        (base_url, server)
+    }
+
+    fn response(status: &str, headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            headers,
+            body.len(),
+            body
+        )
+    }
+
+    #[test]
+    fn test_search_templates_from_github() {
+        let body = r#"{"items": [{"name": "rust-cli", "owner": {"login": "boilerplato"}, "description": "A Rust CLI",
+            "html_url": "https://github.com/boilerplato/rust-cli", "stargazers_count": 42,
+            "updated_at": "2024-03-01T10:00:00Z", "default_branch": "main", "topics": ["rust"]}]}"#;
+        let (base_url, server) = serve(vec![
+            response("200 OK", "", body),
+            response("200 OK", "", body),
+            response(
+                "403 Forbidden",
+                "X-RateLimit-Remaining: 0\r\nX-RateLimit-Reset: 1709287205\r\n",
+                r#"{"message": "API rate limit exceeded"}"#,
+            ),
+            response("500 Internal Server Error", "", r#"{"message": "Server Error"}"#),
+        ]);
+
+        let user_config = UserConfig::parse(
+            format!(
+                "\
+githubApiUrl: {}
+credentials:
+  127.0.0.1:
+    token: s3cret
+",
+                base_url
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let options = SearchOptions::default();
+
+        let results =
+            search_templates_from_github(&user_config, &UserConfig::default(), "rust", &options, &|_| false).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].template, "rust-cli");
+        assert_eq!(results[0].stars, Some(42));
+        assert_eq!(results[0].default_branch.as_deref(), Some("main"));
+
+        // A registry having the name would take the bare name over.
+        let results = search_templates_from_github(&user_config, &UserConfig::default(), "rust", &options, &|name| {
+            name == "rust-cli"
+        })
+        .unwrap();
+        assert_eq!(results[0].template, "boilerplato/rust-cli");
+
+        let err = search_templates_from_github(&user_config, &UserConfig::default(), "rust", &options, &|_| false)
+            .unwrap_err();
+        assert!(err.to_string().contains("rate limit"));
+        assert!(err.to_string().contains("2024-03-01 10:00:05 UTC"));
+
+        let err = search_templates_from_github(&user_config, &UserConfig::default(), "rust", &options, &|_| false)
+            .unwrap_err();
+        assert!(err.to_string().contains("500 Internal Server Error: Server Error"));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("get /api/v3/search/repositories?q=rust+"));
+        assert!(requests.iter().all(|req| req.contains("authorization: token s3cret")));
+    }
+
+    #[test]
+    fn test_search_templates_from_github_of_project_api_url() {
+        let (base_url, server) = serve(vec![response("200 OK", "", r#"{"items": []}"#)]);
+
+        // The API base comes from a `.boilerplatorc`, so the env tokens aren't sent to it.
+        let project_config = UserConfig::parse(format!("githubApiUrl: {}", base_url).as_str()).unwrap();
+        env::set_var(constants::GITHUB_TOKEN_ENV_VAR, "s3cret");
+
+        let results = search_templates_from_github(
+            &project_config,
+            &UserConfig::default(),
+            "rust",
+            &SearchOptions::default(),
+            &|_| false,
+        )
+        .unwrap();
+        assert!(results.is_empty());
+
+        let requests = server.join().unwrap();
+        assert!(!requests[0].contains("authorization"));
+    }
+
+    #[test]
+    fn test_picked_template() {
+        let results = vec![
+            SearchResult {
+                name: "rust-cli".to_owned(),
+                template: "rust-cli".to_owned(),
+                source: "https://github.com/boilerplato/rust-cli".to_owned(),
+                ..SearchResult::default()
+            },
+            SearchResult {
+                name: "api".to_owned(),
+                template: "api".to_owned(),
+                source: "corp:platform/api-template".to_owned(),
+                ..SearchResult::default()
+            },
+        ];
+
+        assert_eq!(picked_result(&results, &Value::from(2)).unwrap().name, "api");
+        assert_eq!(picked_result(&results, &Value::from(1.0)).unwrap().name, "rust-cli");
+        assert!(picked_result(&results, &Value::from(0)).is_err());
+        assert!(picked_result(&results, &Value::from(3)).is_err());
+        assert!(picked_result(&results, &Value::from(1.5)).is_err());
+        assert!(picked_result(&results, &Value::Null).is_err());
+
+        assert_eq!(
+            picked_template(&results[1], &Value::from("my-api")),
+            ("corp:platform/api-template".to_owned(), "my-api".to_owned())
+        );
+        assert_eq!(
+            picked_template(&results[0], &Value::Null),
+            (
+                "https://github.com/boilerplato/rust-cli".to_owned(),
+                "rust-cli".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn test_github_search_url() {
+        let options = SearchOptions {
+            page: 2,
+            limit: 10,
+            sort: SearchSort::Updated,
+            topics: vec!["cli".to_owned()],
+            language: Some("c++".to_owned()),
+            ..SearchOptions::default()
+        };
+
+        let url = github_search_url(
+            "https://api.github.com/search/repositories",
+            "web app&x=1",
+            &["our-org", "boilerplato"],
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://api.github.com/search/repositories?\
+             q=web+app%26x%3D1+in%3Aname%2Cdescription+org%3Aour-org+org%3Aboilerplato+topic%3Acli+language%3Ac%2B%2B\
+             &sort=updated&order=desc&per_page=10&page=2"
+        );
+
+        let url = github_search_url(
+            "https://api.github.com/search/repositories",
+            "rust",
+            &["boilerplato"],
+            &SearchOptions {
+                sort: SearchSort::Name,
+                ..SearchOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://api.github.com/search/repositories?\
+             q=rust+in%3Aname%2Cdescription+org%3Aboilerplato&per_page=30&page=1"
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use crate::constants;
+use crate::generator::CachePolicy;
+use crate::types::OutputFormat;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SearchSort {
+    Stars,
+    Updated,
+    Name,
+}
+
+impl SearchSort {
+    pub fn parse<S: AsRef<str>>(sort: S) -> Option<SearchSort> {
+        match sort.as_ref().trim().to_lowercase().as_str() {
+            constants::SEARCH_SORT_STARS => Some(SearchSort::Stars),
+            constants::SEARCH_SORT_UPDATED => Some(SearchSort::Updated),
+            constants::SEARCH_SORT_NAME => Some(SearchSort::Name),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    /// The 1-based result page, the GitHub results and the registry results are paged separately.
+    pub page: usize,
+    /// The number of results per page.
+    pub limit: usize,
+    pub sort: SearchSort,
+    /// The templates must have all the topics, the registry templates must have them as tags.
+    pub topics: Vec<String>,
+    pub language: Option<String>,
+    pub format: OutputFormat,
+    /// How the GitHub responses and the remote registry indexes are cached, none disables the cache.
+    pub cache_policy: Option<CachePolicy>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            page: 1,
+            limit: constants::SEARCH_DEFAULT_LIMIT,
+            sort: SearchSort::Stars,
+            topics: Vec::new(),
+            language: None,
+            format: OutputFormat::Text,
+            cache_policy: None,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnswersFile {
+    pub boilerplato_version: String,
+    pub template: TemplateProvenance,
+    #[serde(default)]
+    pub data: BTreeMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateProvenance {
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    pub commit: Option<String>,
+}
+
+impl AnswersFile {
+    pub fn parse(text: &str) -> crate::Result<AnswersFile> {
+        serde_yaml::from_str::<AnswersFile>(text).wrap()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> crate::Result<AnswersFile> {
+        let path = path.as_ref();
+
+        let text = fs::read_to_string(path).context(format!(
+            "Couldn't read the answers file: {}",
+            path.to_str().unwrap_or("")
+        ))?;
+
+        AnswersFile::parse(text.as_str()).context(format!("Invalid answers file: {}", path.to_str().unwrap_or("")))
+    }
+
+    pub fn to_yaml(&self) -> crate::Result<String> {
+        serde_yaml::to_string(self).context("Couldn't serialize the answers file")
+    }
+}
@@ -0,0 +1,31 @@
+use crate::constants;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Colours the output unless disabled through `$NO_COLOR` or `$CLICOLOR`.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse<S: AsRef<str>>(choice: S) -> Option<ColorChoice> {
+        match choice.as_ref().trim().to_lowercase().as_str() {
+            constants::COLOR_CHOICE_AUTO => Some(ColorChoice::Auto),
+            constants::COLOR_CHOICE_ALWAYS => Some(ColorChoice::Always),
+            constants::COLOR_CHOICE_NEVER => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self) {
+        match self {
+            ColorChoice::Auto => colored::control::unset_override(),
+            ColorChoice::Always => colored::control::set_override(true),
+            ColorChoice::Never => colored::control::set_override(false),
+        }
+    }
+}
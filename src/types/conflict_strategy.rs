@@ -0,0 +1,45 @@
+use crate::constants;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    #[default]
+    Backup,
+    Overwrite,
+    Skip,
+    Prompt,
+    Fail,
+}
+
+impl ConflictStrategy {
+    pub fn parse<S: AsRef<str>>(strategy: S) -> Option<ConflictStrategy> {
+        match strategy.as_ref().trim().to_lowercase().as_str() {
+            constants::CONFLICT_STRATEGY_BACKUP => Some(ConflictStrategy::Backup),
+            constants::CONFLICT_STRATEGY_OVERWRITE => Some(ConflictStrategy::Overwrite),
+            constants::CONFLICT_STRATEGY_SKIP => Some(ConflictStrategy::Skip),
+            constants::CONFLICT_STRATEGY_PROMPT => Some(ConflictStrategy::Prompt),
+            constants::CONFLICT_STRATEGY_FAIL => Some(ConflictStrategy::Fail),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflict_strategy_parse() {
+        assert_eq!(ConflictStrategy::parse("backup"), Some(ConflictStrategy::Backup));
+        assert_eq!(
+            ConflictStrategy::parse(" Overwrite "),
+            Some(ConflictStrategy::Overwrite)
+        );
+        assert_eq!(ConflictStrategy::parse("SKIP"), Some(ConflictStrategy::Skip));
+        assert_eq!(ConflictStrategy::parse("prompt"), Some(ConflictStrategy::Prompt));
+        assert_eq!(ConflictStrategy::parse("fail"), Some(ConflictStrategy::Fail));
+        assert_eq!(ConflictStrategy::parse("merge"), None);
+        assert_eq!(ConflictStrategy::parse(""), None);
+    }
+}
@@ -1,3 +1,11 @@
+pub use self::answers_file::{AnswersFile, TemplateProvenance};
+pub use self::color_choice::ColorChoice;
+pub use self::conflict_strategy::ConflictStrategy;
+pub use self::output_format::OutputFormat;
 pub use self::template_config::{CondFileMap, ConfigFileType, TemplateConfig, TemplateData, TemplateDataType};
 
+mod answers_file;
+mod color_choice;
+mod conflict_strategy;
+mod output_format;
 mod template_config;
@@ -0,0 +1,20 @@
+use crate::constants;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn parse<S: AsRef<str>>(format: S) -> Option<OutputFormat> {
+        match format.as_ref().trim().to_lowercase().as_str() {
+            constants::OUTPUT_FORMAT_TEXT => Some(OutputFormat::Text),
+            constants::OUTPUT_FORMAT_JSON => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use crate::constants;
 use crate::prelude::*;
+use crate::types::ConflictStrategy;
 use serde::{Deserialize, Serialize};
 use serde_json::{Number, Value};
 use std::collections::HashMap;
+use std::path::Path;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -14,6 +16,7 @@ pub struct TemplateConfig {
     pub files: Option<HashMap<String, Value>>,
     #[serde(skip)]
     pub files_map: Option<HashMap<String, CondFileMap>>,
+    pub conflicts: Option<HashMap<String, ConflictStrategy>>,
     pub post_generate: Option<Value>,
     pub help_text: Option<Value>,
 }
@@ -26,6 +29,9 @@ pub struct TemplateMeta {
     pub path: String,
     #[serde(default)]
     pub extension: String,
+    /// Clone the git submodules of the template repo too.
+    #[serde(default)]
+    pub submodules: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -55,6 +61,8 @@ pub enum TemplateDataType {
     ArrayNumber,
     #[serde(rename = "semver")]
     Semver,
+    #[serde(rename = "secret")]
+    Secret,
 }
 
 #[derive(Debug, Clone)]
@@ -78,6 +86,7 @@ impl TemplateDataType {
             TemplateDataType::ArrayString => Value::Array(Vec::default()),
             TemplateDataType::ArrayNumber => Value::Array(Vec::default()),
             TemplateDataType::Semver => Value::String(constants::TEMPLATE_TYPE_SEMVER_DEFAULT_VALUE.to_owned()),
+            TemplateDataType::Secret => Value::String(String::default()),
         }
     }
 }
@@ -89,4 +98,49 @@ impl TemplateConfig {
             ConfigFileType::YAML => serde_yaml::from_str::<TemplateConfig>(config_text).wrap(),
         }
     }
+
+    /// Finds the conflict strategy configured for a generated file, a strategy set for a dir applies to
+    /// all the files under it.
+    pub fn conflict_strategy_for(&self, rel_path_in_project_dir: &Path) -> Option<ConflictStrategy> {
+        let conflicts = self.conflicts.as_ref()?;
+
+        rel_path_in_project_dir.ancestors().find_map(|path| {
+            conflicts
+                .iter()
+                .find(|(key, _)| Path::new(key.trim_start_matches("./").trim_end_matches('/')) == path)
+                .map(|(_, strategy)| *strategy)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflict_strategy_for() {
+        let config = TemplateConfig::parse(
+            "\
+version: 1.0.0
+template:
+  engine: handlebars
+conflicts:
+  ./README.md: skip
+  ./src/config/: prompt
+  src/config/secrets.rs: fail
+",
+            ConfigFileType::YAML,
+        )
+        .unwrap();
+
+        let strategy_for = |path: &str| config.conflict_strategy_for(Path::new(path));
+
+        assert_eq!(strategy_for("README.md"), Some(ConflictStrategy::Skip));
+        assert_eq!(strategy_for("docs/README.md"), None);
+        assert_eq!(strategy_for("src/config/app.rs"), Some(ConflictStrategy::Prompt));
+        assert_eq!(strategy_for("src/config/db/pool.rs"), Some(ConflictStrategy::Prompt));
+        assert_eq!(strategy_for("src/config/secrets.rs"), Some(ConflictStrategy::Fail));
+        assert_eq!(strategy_for("src/configs.rs"), None);
+        assert_eq!(strategy_for("src/main.rs"), None);
+    }
 }
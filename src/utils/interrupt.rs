@@ -0,0 +1,95 @@
+use crate::utils::temp_dir::remove_live_temp_dirs;
+use colored::*;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+struct InterruptState {
+    interrupted: AtomicBool,
+    critical_sections: AtomicUsize,
+}
+
+static STATE: InterruptState = InterruptState {
+    interrupted: AtomicBool::new(false),
+    critical_sections: AtomicUsize::new(0),
+};
+
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Installs the Ctrl-C handler. Outside of a critical section the temporary dirs are removed and the process
+/// exits immediately, inside it the interruption is only flagged so that the changes can be rolled back.
+pub fn install_handler() {
+    let _ = ctrlc::set_handler(|| {
+        STATE.interrupted.store(true, Ordering::SeqCst);
+
+        if STATE.critical_sections.load(Ordering::SeqCst) == 0 {
+            remove_live_temp_dirs();
+            eprintln!("\n{} Interrupted", "error:".red());
+            process::exit(INTERRUPTED_EXIT_CODE);
+        }
+    });
+}
+
+pub fn is_interrupted() -> bool {
+    STATE.interrupted.load(Ordering::SeqCst)
+}
+
+pub struct CriticalSection {
+    state: &'static InterruptState,
+    left: bool,
+}
+
+impl CriticalSection {
+    pub fn enter() -> CriticalSection {
+        CriticalSection::enter_state(&STATE)
+    }
+
+    fn enter_state(state: &'static InterruptState) -> CriticalSection {
+        state.critical_sections.fetch_add(1, Ordering::SeqCst);
+        CriticalSection { state, left: false }
+    }
+
+    /// Leaves the critical section, it fails if the process was interrupted inside it so that the caller stops
+    /// before its next steps.
+    pub fn leave(mut self) -> crate::Result<()> {
+        self.left = true;
+        self.state.critical_sections.fetch_sub(1, Ordering::SeqCst);
+
+        if self.state.interrupted.load(Ordering::SeqCst) {
+            return Err(crate::Error::new("Interrupted"));
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for CriticalSection {
+    fn drop(&mut self) {
+        if !self.left {
+            self.state.critical_sections.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_critical_section_leave() {
+        // A state of its own keeps the other tests from seeing the interruption.
+        let state: &'static InterruptState = Box::leak(Box::new(InterruptState {
+            interrupted: AtomicBool::new(false),
+            critical_sections: AtomicUsize::new(0),
+        }));
+
+        assert!(CriticalSection::enter_state(state).leave().is_ok());
+
+        let critical_section = CriticalSection::enter_state(state);
+        state.interrupted.store(true, Ordering::SeqCst);
+        assert!(critical_section.leave().is_err());
+        assert_eq!(state.critical_sections.load(Ordering::SeqCst), 0);
+
+        drop(CriticalSection::enter_state(state));
+        assert_eq!(state.critical_sections.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::prelude::*;
+use crate::utils::gen_uuid;
+use lazy_static::lazy_static;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+lazy_static! {
+    static ref LIVE_TEMP_DIRS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+}
+
+/// A temporary dir which is removed when it goes out of scope, or when the process is interrupted.
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> crate::Result<TempDir> {
+        TempDir::new_at(env::temp_dir().join(gen_uuid()))
+    }
+
+    pub fn new_at<P: Into<PathBuf>>(path: P) -> crate::Result<TempDir> {
+        let path = path.into();
+
+        fs::create_dir_all(path.as_path()).context(format!(
+            "Couldn't create a temporary folder: {}",
+            path.to_str().unwrap_or("")
+        ))?;
+
+        if let Ok(mut dirs) = LIVE_TEMP_DIRS.lock() {
+            dirs.push(path.clone());
+        }
+
+        Ok(TempDir { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Removes the dir and reports the failure, dropping it removes the dir silently.
+    pub fn close(self) -> crate::Result<()> {
+        fs::remove_dir_all(self.path.as_path()).context(format!(
+            "Couldn't remove the temporary folder: {}",
+            self.path.to_str().unwrap_or("")
+        ))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if let Ok(mut dirs) = LIVE_TEMP_DIRS.lock() {
+            dirs.retain(|dir| dir != &self.path);
+        }
+
+        let _ = fs::remove_dir_all(self.path.as_path());
+    }
+}
+
+/// Removes all the live temporary dirs, it's called when the process is about to exit abruptly.
+pub fn remove_live_temp_dirs() {
+    if let Ok(dirs) = LIVE_TEMP_DIRS.lock() {
+        for dir in dirs.iter() {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}